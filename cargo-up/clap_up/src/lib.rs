@@ -14,8 +14,14 @@ pub fn runner() -> Runner {
             Version::new("3.0.0-rc.0")
                 .unwrap()
                 .peers(&["structopt"])
-                // .replace_dep("structopt", "clap", features = ["derive"])
-                .rename_structs("clap::args::subcommand", &[["SubCommand", "App"]])
+                .replace_dep(
+                    "structopt",
+                    "clap",
+                    "3.0.0-rc.0",
+                    &["derive"],
+                    &[["structopt::StructOpt", "clap::StructOpt"]],
+                )
+                .rename_structs("clap", &[["SubCommand", "App"]])
                 .rename_methods(
                     "structopt::StructOpt",
                     &[
@@ -27,7 +33,7 @@ pub fn runner() -> Runner {
                     ],
                 )
                 .rename_variants(
-                    "clap::errors::ErrorKind",
+                    "clap::ErrorKind",
                     &[
                         ["HelpDisplayed", "DisplayHelp"],
                         ["VersionDisplayed", "DisplayVersion"],
@@ -38,7 +44,7 @@ pub fn runner() -> Runner {
                     ],
                 )
                 .rename_variants(
-                    "clap::app::settings::AppSettings",
+                    "clap::AppSettings",
                     &[
                         ["DisableHelpFlags", "DisableHelpFlag"],
                         ["DisableVersion", "DisableVersionFlag"],
@@ -47,7 +53,7 @@ pub fn runner() -> Runner {
                     ],
                 )
                 .rename_variants(
-                    "clap::args::settings::ArgSettings",
+                    "clap::ArgSettings",
                     &[
                         ["CaseInsensitive", "IgnoreCase"],
                         ["AllowLeadingHyphen", "AllowHyphenValues"],
@@ -55,7 +61,7 @@ pub fn runner() -> Runner {
                     ],
                 )
                 .rename_methods(
-                    "clap::app::App",
+                    "clap::App",
                     &[
                         ["from_yaml", "from"],
                         ["arg_from_usage", "arg"],
@@ -76,7 +82,7 @@ pub fn runner() -> Runner {
                     ],
                 )
                 .rename_methods(
-                    "clap::args::arg::Arg",
+                    "clap::Arg",
                     &[
                         ["help", "about"],
                         ["from_usage", "from"],
@@ -92,15 +98,15 @@ pub fn runner() -> Runner {
                     ],
                 )
                 .rename_methods(
-                    "clap::args::group::ArgGroup",
+                    "clap::ArgGroup",
                     &[["from_yaml", "from"], ["with_name", "new"]],
                 )
                 .rename_methods(
-                    "clap::args::subcommand::SubCommand",
+                    "clap::SubCommand",
                     &[["from_yaml", "from"], ["with_name", "new"]],
                 )
-                .rename_members("clap::errors::Error", &[["message", "cause"]]) // TODO: check
-                .hook_method_call_expr_on("clap::app::App", "args_from_usage", |u, n, _| {
+                .rename_members("clap::Error", &[["message", "cause"]]) // TODO: check
+                .hook_method_call_expr_on("clap::App", "args_from_usage", |u, n, _| {
                     let arg = n.arg_list().unwrap().args().last();
 
                     u.insert(
@@ -108,7 +114,7 @@ pub fn runner() -> Runner {
                         ".lines().map(|l| l.trim()).filter(|l| !l.is_empty())",
                     );
                 })
-                .hook_path_expr_on("clap::app::App", "with_defaults", |u, n, _| {
+                .hook_path_expr_on("clap::App", "with_defaults", |u, n, _| {
                     if let Some(parent) = n.syntax().parent() {
                         if let Some(call_expr) = CallExpr::cast(parent) {
                             // TODO: Add full path
@@ -119,7 +125,7 @@ pub fn runner() -> Runner {
                         }
                     }
                 })
-                .hook_method_call_expr_on("clap::app::App", "version_message", |u, n, _| {
+                .hook_method_call_expr_on("clap::App", "version_message", |u, n, _| {
                     let arg_list = n.arg_list().unwrap();
 
                     u.insert(
@@ -128,7 +134,7 @@ pub fn runner() -> Runner {
                     );
                     u.insert(arg_list.r_paren_token().unwrap().text_range().start(), ")")
                 })
-                .hook_method_call_expr_on("clap::app::App", "version_short", |u, n, _| {
+                .hook_method_call_expr_on("clap::App", "version_short", |u, n, _| {
                     let arg_list = n.arg_list().unwrap();
 
                     u.insert(
@@ -140,7 +146,7 @@ pub fn runner() -> Runner {
                         ".trim_start_matches(|c| c == '-').chars().nth(0).unwrap_or('V'))",
                     )
                 })
-                .hook_method_call_expr_on("clap::app::App", "help_message", |u, n, _| {
+                .hook_method_call_expr_on("clap::App", "help_message", |u, n, _| {
                     let arg_list = n.arg_list().unwrap();
 
                     u.insert(
@@ -149,7 +155,7 @@ pub fn runner() -> Runner {
                     );
                     u.insert(arg_list.r_paren_token().unwrap().text_range().start(), ")")
                 })
-                .hook_method_call_expr_on("clap::app::App", "help_short", |u, n, _| {
+                .hook_method_call_expr_on("clap::App", "help_short", |u, n, _| {
                     let arg_list = n.arg_list().unwrap();
 
                     u.insert(
@@ -69,146 +69,154 @@ macro_rules! visiting {
     };
 }
 
-visiting!(
-    SOURCE_FILE,
-    STRUCT,
-    UNION,
-    ENUM,
-    FN,
-    RET_TYPE,
-    EXTERN_CRATE,
-    MODULE,
-    USE,
-    STATIC,
-    CONST,
-    TRAIT,
-    TRAIT_ALIAS,
-    IMPL,
-    TYPE_ALIAS,
-    MACRO_CALL,
-    MACRO_RULES,
-    TOKEN_TREE,
-    MACRO_DEF,
-    PAREN_TYPE,
-    TUPLE_TYPE,
-    MACRO_TYPE,
-    NEVER_TYPE,
-    PATH_TYPE,
-    PTR_TYPE,
-    ARRAY_TYPE,
-    SLICE_TYPE,
-    REF_TYPE,
-    INFER_TYPE,
-    FN_PTR_TYPE,
-    FOR_TYPE,
-    IMPL_TRAIT_TYPE,
-    DYN_TRAIT_TYPE,
-    OR_PAT,
-    PAREN_PAT,
-    REF_PAT,
-    BOX_PAT,
-    IDENT_PAT,
-    WILDCARD_PAT,
-    REST_PAT,
-    PATH_PAT,
-    RECORD_PAT,
-    RECORD_PAT_FIELD_LIST,
-    RECORD_PAT_FIELD,
-    TUPLE_STRUCT_PAT,
-    TUPLE_PAT,
-    SLICE_PAT,
-    RANGE_PAT,
-    LITERAL_PAT,
-    MACRO_PAT,
-    CONST_BLOCK_PAT,
-    TUPLE_EXPR,
-    ARRAY_EXPR,
-    PAREN_EXPR,
-    PATH_EXPR,
-    CLOSURE_EXPR,
-    IF_EXPR,
-    WHILE_EXPR,
-    LOOP_EXPR,
-    FOR_EXPR,
-    CONTINUE_EXPR,
-    BREAK_EXPR,
-    LABEL,
-    BLOCK_EXPR,
-    STMT_LIST,
-    RETURN_EXPR,
-    BECOME_EXPR,
-    YIELD_EXPR,
-    YEET_EXPR,
-    LET_EXPR,
-    UNDERSCORE_EXPR,
-    MACRO_EXPR,
-    MATCH_EXPR,
-    MATCH_ARM_LIST,
-    MATCH_ARM,
-    MATCH_GUARD,
-    RECORD_EXPR,
-    RECORD_EXPR_FIELD_LIST,
-    RECORD_EXPR_FIELD,
-    OFFSET_OF_EXPR,
-    ASM_EXPR,
-    FORMAT_ARGS_EXPR,
-    FORMAT_ARGS_ARG,
-    CALL_EXPR,
-    INDEX_EXPR,
-    METHOD_CALL_EXPR,
-    FIELD_EXPR,
-    AWAIT_EXPR,
-    TRY_EXPR,
-    CAST_EXPR,
-    REF_EXPR,
-    PREFIX_EXPR,
-    RANGE_EXPR,
-    BIN_EXPR,
-    EXTERN_BLOCK,
-    EXTERN_ITEM_LIST,
-    VARIANT,
-    RECORD_FIELD_LIST,
-    RECORD_FIELD,
-    TUPLE_FIELD_LIST,
-    TUPLE_FIELD,
-    VARIANT_LIST,
-    ITEM_LIST,
-    ASSOC_ITEM_LIST,
-    ATTR,
-    META,
-    USE_TREE,
-    USE_TREE_LIST,
-    PATH,
-    PATH_SEGMENT,
-    LITERAL,
-    RENAME,
-    VISIBILITY,
-    WHERE_CLAUSE,
-    WHERE_PRED,
-    ABI,
-    NAME,
-    NAME_REF,
-    LET_STMT,
-    LET_ELSE,
-    EXPR_STMT,
-    GENERIC_PARAM_LIST,
-    GENERIC_PARAM,
-    LIFETIME_PARAM,
-    TYPE_PARAM,
-    CONST_PARAM,
-    GENERIC_ARG_LIST,
-    LIFETIME,
-    LIFETIME_ARG,
-    TYPE_ARG,
-    ASSOC_TYPE_ARG,
-    CONST_ARG,
-    PARAM_LIST,
-    PARAM,
-    SELF_PARAM,
-    ARG_LIST,
-    TYPE_BOUND,
-    TYPE_BOUND_LIST,
-    MACRO_ITEMS,
-    MACRO_STMTS,
-    MACRO_EAGER_INPUT,
-);
+/// Calls the given macro with every `SyntaxKind` the visitor dispatches on.
+#[macro_export]
+macro_rules! kinds {
+    ($callback:ident) => {
+        $callback!(
+            SOURCE_FILE,
+            STRUCT,
+            UNION,
+            ENUM,
+            FN,
+            RET_TYPE,
+            EXTERN_CRATE,
+            MODULE,
+            USE,
+            STATIC,
+            CONST,
+            TRAIT,
+            TRAIT_ALIAS,
+            IMPL,
+            TYPE_ALIAS,
+            MACRO_CALL,
+            MACRO_RULES,
+            TOKEN_TREE,
+            MACRO_DEF,
+            PAREN_TYPE,
+            TUPLE_TYPE,
+            MACRO_TYPE,
+            NEVER_TYPE,
+            PATH_TYPE,
+            PTR_TYPE,
+            ARRAY_TYPE,
+            SLICE_TYPE,
+            REF_TYPE,
+            INFER_TYPE,
+            FN_PTR_TYPE,
+            FOR_TYPE,
+            IMPL_TRAIT_TYPE,
+            DYN_TRAIT_TYPE,
+            OR_PAT,
+            PAREN_PAT,
+            REF_PAT,
+            BOX_PAT,
+            IDENT_PAT,
+            WILDCARD_PAT,
+            REST_PAT,
+            PATH_PAT,
+            RECORD_PAT,
+            RECORD_PAT_FIELD_LIST,
+            RECORD_PAT_FIELD,
+            TUPLE_STRUCT_PAT,
+            TUPLE_PAT,
+            SLICE_PAT,
+            RANGE_PAT,
+            LITERAL_PAT,
+            MACRO_PAT,
+            CONST_BLOCK_PAT,
+            TUPLE_EXPR,
+            ARRAY_EXPR,
+            PAREN_EXPR,
+            PATH_EXPR,
+            CLOSURE_EXPR,
+            IF_EXPR,
+            WHILE_EXPR,
+            LOOP_EXPR,
+            FOR_EXPR,
+            CONTINUE_EXPR,
+            BREAK_EXPR,
+            LABEL,
+            BLOCK_EXPR,
+            STMT_LIST,
+            RETURN_EXPR,
+            BECOME_EXPR,
+            YIELD_EXPR,
+            YEET_EXPR,
+            LET_EXPR,
+            UNDERSCORE_EXPR,
+            MACRO_EXPR,
+            MATCH_EXPR,
+            MATCH_ARM_LIST,
+            MATCH_ARM,
+            MATCH_GUARD,
+            RECORD_EXPR,
+            RECORD_EXPR_FIELD_LIST,
+            RECORD_EXPR_FIELD,
+            OFFSET_OF_EXPR,
+            ASM_EXPR,
+            FORMAT_ARGS_EXPR,
+            FORMAT_ARGS_ARG,
+            CALL_EXPR,
+            INDEX_EXPR,
+            METHOD_CALL_EXPR,
+            FIELD_EXPR,
+            AWAIT_EXPR,
+            TRY_EXPR,
+            CAST_EXPR,
+            REF_EXPR,
+            PREFIX_EXPR,
+            RANGE_EXPR,
+            BIN_EXPR,
+            EXTERN_BLOCK,
+            EXTERN_ITEM_LIST,
+            VARIANT,
+            RECORD_FIELD_LIST,
+            RECORD_FIELD,
+            TUPLE_FIELD_LIST,
+            TUPLE_FIELD,
+            VARIANT_LIST,
+            ITEM_LIST,
+            ASSOC_ITEM_LIST,
+            ATTR,
+            META,
+            USE_TREE,
+            USE_TREE_LIST,
+            PATH,
+            PATH_SEGMENT,
+            LITERAL,
+            RENAME,
+            VISIBILITY,
+            WHERE_CLAUSE,
+            WHERE_PRED,
+            ABI,
+            NAME,
+            NAME_REF,
+            LET_STMT,
+            LET_ELSE,
+            EXPR_STMT,
+            GENERIC_PARAM_LIST,
+            GENERIC_PARAM,
+            LIFETIME_PARAM,
+            TYPE_PARAM,
+            CONST_PARAM,
+            GENERIC_ARG_LIST,
+            LIFETIME,
+            LIFETIME_ARG,
+            TYPE_ARG,
+            ASSOC_TYPE_ARG,
+            CONST_ARG,
+            PARAM_LIST,
+            PARAM,
+            SELF_PARAM,
+            ARG_LIST,
+            TYPE_BOUND,
+            TYPE_BOUND_LIST,
+            MACRO_ITEMS,
+            MACRO_STMTS,
+            MACRO_EAGER_INPUT,
+        );
+    };
+}
+
+kinds!(visiting);
@@ -4,7 +4,7 @@ use crate::utils::{
     normalize, Error, Result, INTERNAL_ERR,
 };
 
-use cargo_metadata::{Metadata, Package};
+use cargo_metadata::Metadata;
 use clap::{crate_version, Parser};
 use oclif::term::{OUT_YELLOW, TERM_OUT};
 use semver::Version;
@@ -34,10 +34,41 @@ pub struct Dep {
     #[clap(long)]
     lib_version: Option<Version>,
 
+    /// Print the changes as a unified diff instead of applying them
+    #[clap(long)]
+    dry_run: bool,
+
+    /// Write the changes as a patch file instead of printing them
+    #[clap(long, value_name = "path", requires = "dry-run")]
+    patch: Option<String>,
+
+    /// Remove the imports of the dependency which are left unused by the upgrade
+    #[clap(long)]
+    remove_unused_imports: bool,
+
+    /// Format the changed items, or the whole changed files, with rustfmt
+    #[clap(
+        long,
+        value_name = "scope",
+        possible_values = &["items", "files"],
+        min_values = 0,
+        require_equals = true,
+        default_missing_value = "items"
+    )]
+    format: Option<String>,
+
+    /// Fail when a hook of the upgrader does not match anything in the dependency
+    #[clap(long)]
+    strict: bool,
+
     /// Suppress cargo build output
     #[clap(long, hide = true)]
     suppress_cargo_output: bool,
 
+    /// Specify directory to cache the preloaded dependencies in
+    #[clap(long, value_name = "path", hide = true)]
+    preload_cache: Option<String>,
+
     /// Specify path for upgrader
     #[cfg(debug_assertions)]
     #[clap(long, hide = true, requires_all = &["upgrader-name", "dep-version", "lib-path"])]
@@ -52,6 +83,11 @@ pub struct Dep {
     #[cfg(debug_assertions)]
     #[clap(long, hide = true, requires_all = &["upgrader-name", "upgrader-path", "dep-version"])]
     lib_path: Option<String>,
+
+    /// Specify version of the dependency to upgrade from instead of the locked one
+    #[cfg(debug_assertions)]
+    #[clap(long, hide = true, requires_all = &["upgrader-name", "upgrader-path", "dep-version"])]
+    from_version: Option<Version>,
 }
 
 fn get_path(path: &Option<String>) -> Result<String> {
@@ -79,11 +115,12 @@ impl Dep {
         if let Some(upgrader_name) = &self.upgrader_name {
             // Use the given options on CLI for local testing
             let dep_version = self.dep_version.as_ref().expect(INTERNAL_ERR).to_string();
+            let from_version = self.from_version.as_ref().unwrap_or(&pkg.version);
 
             self.upgrade(
                 &metadata,
                 &dep,
-                pkg,
+                from_version,
                 upgrader_name,
                 &get_path(&self.upgrader_path)?,
                 &dep_version,
@@ -124,31 +161,41 @@ impl Dep {
                 .map_err(|_| Error::NoDependency { dep: dep.clone() })?
                 .versions
                 .into_iter()
+                .filter(|x| !x.yanked)
                 .map(|x| Version::parse(&x.num).map_err(|_| Error::BadRegistry))
                 .rev()
                 .collect::<Result<Vec<Version>>>()?
                 .into_iter()
-                .filter(|x| *x > pkg.version)
+                .filter(|x| *x > pkg.version && x.pre.is_empty())
                 .collect::<Vec<_>>();
 
-            for dep_version in versions {
-                TERM_OUT.write_line(&format!(
-                    "Trying to upgrade {} dependency to {} version ...",
-                    OUT_YELLOW.apply_to(&self.dep),
-                    OUT_YELLOW.apply_to(&dep_version),
-                ))?;
-                TERM_OUT.flush()?;
-
-                self.upgrade(
-                    &metadata,
-                    &dep,
-                    pkg,
-                    &upgrader_krate.krate.name,
-                    &upgrader_version,
-                    &dep_version.to_string(),
-                    &lib_version,
-                )?;
-            }
+            // Apply the changes of all the newer versions at once, only upgrading to a
+            // yanked or pre-release version when asked for explicitly
+            let dep_version = match self
+                .dep_version
+                .clone()
+                .or_else(|| versions.last().cloned())
+            {
+                Some(x) => x,
+                None => return Ok(()),
+            };
+
+            TERM_OUT.write_line(&format!(
+                "Trying to upgrade {} dependency to {} version ...",
+                OUT_YELLOW.apply_to(&self.dep),
+                OUT_YELLOW.apply_to(&dep_version),
+            ))?;
+            TERM_OUT.flush()?;
+
+            self.upgrade(
+                &metadata,
+                &dep,
+                &pkg.version,
+                &upgrader_krate.krate.name,
+                &upgrader_version,
+                &dep_version.to_string(),
+                &lib_version,
+            )?;
 
             Ok(())
         }
@@ -158,7 +205,7 @@ impl Dep {
         &self,
         metadata: &Metadata,
         dep: &String,
-        pkg: &Package,
+        from_version: &Version,
         upgrader_name: &str,
         upgrader_version: &str,
         dep_version: &str,
@@ -176,6 +223,23 @@ impl Dep {
 
         create_dir_all(cache_dir.join("src"))?;
 
+        let format = match self.format.as_deref() {
+            Some("files") => "Some(Format::Files)",
+            Some(_) => "Some(Format::Items)",
+            None => "None",
+        };
+
+        // Runner is executed in the cache dir, so the given paths need to be absolute
+        let patch = match &self.patch {
+            Some(path) => format!("Some(PathBuf::from({:?}))", current_dir()?.join(path)),
+            None => "None".to_string(),
+        };
+
+        let preload_cache = match &self.preload_cache {
+            Some(path) => current_dir()?.join(path),
+            None => cache_dir.join("preload"),
+        };
+
         write(
             cache_dir.join("Cargo.toml"),
             format!(
@@ -201,8 +265,8 @@ impl Dep {
             format!(
                 r#"
                 use oclif::finish;
-                use cargo_up::{{semver::Version, run, Runner}};
-                use std::path::Path;
+                use cargo_up::{{semver::Version, run_range, Format, RunOptions, Runner}};
+                use std::path::{{Path, PathBuf}};
 
                 // To type check the returned runner
                 fn runner() -> Runner {{
@@ -214,12 +278,20 @@ impl Dep {
                         .format_timestamp(None)
                         .init();
 
-                    let result = run(
+                    let result = run_range(
                         Path::new({:?}),
                         "{}",
                         runner(),
                         Version::parse("{}").unwrap(),
                         Version::parse("{}").unwrap(),
+                        RunOptions {{
+                            dry_run: {},
+                            patch: {},
+                            remove_unused_imports: {},
+                            format: {},
+                            strict: {},
+                            cache: Some(PathBuf::from({:?})),
+                        }},
                     );
 
                     finish(result);
@@ -232,8 +304,14 @@ impl Dep {
                     .into_os_string()
                     .to_string_lossy(),
                 dep,
-                pkg.version,
+                from_version,
                 dep_version,
+                self.dry_run,
+                patch,
+                self.remove_unused_imports,
+                format,
+                self.strict,
+                preload_cache,
             ),
         )?;
 
@@ -0,0 +1,252 @@
+use crate::{
+    ra_ap_syntax::{
+        ast::{self, AstNode},
+        NodeOrToken, SyntaxElement, SyntaxKind, TextRange, TextSize,
+    },
+    Semantics, Upgrader,
+};
+
+use ra_ap_hir::{ModuleDef, PathResolution};
+
+/// Change to the arguments of a call. Indices do not count the receiver of a method.
+#[derive(Debug, Clone)]
+pub(crate) enum ArgEdit {
+    /// New position `i` gets the argument which was at `order[i]`
+    Reorder(&'static [usize]),
+    Insert(usize, &'static str),
+    Remove(usize),
+    /// Replaces `{}` in the template with the argument
+    Wrap(usize, &'static str),
+}
+
+/// Applies the edit to the argument list, skipping the first `skip` arguments which
+/// are the receiver of a method called with UFCS.
+pub(crate) fn edit_args(
+    upgrader: &mut Upgrader,
+    arg_list: &ast::ArgList,
+    skip: usize,
+    edit: &ArgEdit,
+) -> Option<()> {
+    let args = arg_list.args().skip(skip).collect::<Vec<_>>();
+
+    match *edit {
+        ArgEdit::Reorder(order) => {
+            if order.len() != args.len() || order.iter().any(|x| *x >= args.len()) {
+                return None;
+            }
+
+            let comments = args
+                .iter()
+                .map(|x| trailing_comments(&x.syntax().clone().into()))
+                .collect::<Vec<_>>();
+
+            // Comments after the arguments go along with them unless a line comment would
+            // end up before other code on its line
+            let movable = order
+                .iter()
+                .enumerate()
+                .all(|(to, from)| !comments[*from].1.contains("//") || comments[to].2);
+
+            for (to, from) in order.iter().enumerate() {
+                upgrader.replace(args[to].syntax().text_range(), args[*from].to_string());
+
+                if movable && comments[to].1 != comments[*from].1 {
+                    upgrader.replace(comments[to].0, comments[*from].1.clone());
+                }
+            }
+        }
+        ArgEdit::Insert(index, expr) => {
+            let all = arg_list.args().count();
+
+            if let Some(arg) = args.get(index) {
+                let text = match line_indent(arg.syntax().clone().into()) {
+                    Some(indent) => format!("{},\n{}", expr, indent),
+                    None => format!("{}, ", expr),
+                };
+
+                upgrader.insert(arg.syntax().text_range().start(), text);
+            } else if let Some(last) = arg_list.args().last() {
+                append_after(upgrader, &last.syntax().clone().into(), expr);
+            } else if all == 0 {
+                upgrader.insert(arg_list.l_paren_token()?.text_range().end(), expr);
+            }
+        }
+        ArgEdit::Remove(index) => {
+            let arg = args.get(index)?.syntax().clone();
+            let all = arg_list.args().collect::<Vec<_>>();
+            let position = all.iter().position(|x| *x.syntax() == arg)?;
+
+            let range = if all.len() == 1 {
+                TextRange::new(
+                    arg_list.l_paren_token()?.text_range().end(),
+                    arg_list.r_paren_token()?.text_range().start(),
+                )
+            } else if line_indent(arg.clone().into()).is_some() {
+                // The argument is on its own line, remove the whole line along with the
+                // comment lines right above it
+                let mut first: SyntaxElement = arg.clone().into();
+
+                while let Some(comment) = first
+                    .prev_sibling_or_token()
+                    .filter(|x| {
+                        x.kind() == SyntaxKind::WHITESPACE
+                            && x.to_string().matches('\n').count() == 1
+                    })
+                    .and_then(|x| x.prev_sibling_or_token())
+                    .filter(|x| x.kind() == SyntaxKind::COMMENT && line_indent(x.clone()).is_some())
+                {
+                    first = comment;
+                }
+
+                let indent = line_indent(first.clone())?;
+                let (end, _) = line_end(&arg.clone().into());
+                let start = first.text_range().start() - TextSize::of(indent.as_str());
+                TextRange::new(start, end + TextSize::of('\n'))
+            } else if position + 1 < all.len() {
+                TextRange::new(
+                    arg.text_range().start(),
+                    all[position + 1].syntax().text_range().start(),
+                )
+            } else {
+                TextRange::new(
+                    all[position - 1].syntax().text_range().end(),
+                    arg.text_range().end(),
+                )
+            };
+
+            upgrader.delete(range);
+        }
+        ArgEdit::Wrap(index, template) => {
+            let arg = args.get(index)?;
+            upgrader.replace(
+                arg.syntax().text_range(),
+                template.replace("{}", &arg.to_string()),
+            );
+        }
+    }
+
+    Some(())
+}
+
+/// Appends the item to a comma separated list after its last element, on a new line if
+/// the elements are each on their own line
+pub(crate) fn append_after(upgrader: &mut Upgrader, last: &SyntaxElement, item: &str) {
+    match line_indent(last.clone()) {
+        Some(indent) => {
+            let (end, has_comma) = line_end(last);
+
+            if has_comma {
+                upgrader.insert(end, format!("\n{}{},", indent, item));
+            } else {
+                upgrader.insert(last.text_range().end(), ",");
+                upgrader.insert(end, format!("\n{}{}", indent, item));
+            }
+        }
+        None => upgrader.insert(comments_end(last), format!(", {}", item)),
+    }
+}
+
+/// Returns the indentation of the element if it starts its own line
+pub(crate) fn line_indent(element: SyntaxElement) -> Option<String> {
+    let prev = element.prev_sibling_or_token()?;
+
+    match prev {
+        NodeOrToken::Token(token) if token.kind() == SyntaxKind::WHITESPACE => {
+            let (_, indent) = token.text().rsplit_once('\n')?;
+            Some(indent.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Returns the end of the comments following the element on the same line
+fn comments_end(element: &SyntaxElement) -> TextSize {
+    let mut end = element.text_range().end();
+    let mut next = element.next_sibling_or_token();
+
+    while let Some(current) = next {
+        match current.kind() {
+            SyntaxKind::COMMENT => end = current.text_range().end(),
+            SyntaxKind::WHITESPACE if !current.to_string().contains('\n') => {}
+            _ => break,
+        }
+
+        next = current.next_sibling_or_token();
+    }
+
+    end
+}
+
+/// Returns the range of the comments following the element on the same line, including
+/// the whitespace before them and starting after its comma, along with their text and
+/// whether the line ends after them
+fn trailing_comments(element: &SyntaxElement) -> (TextRange, String, bool) {
+    let mut start = element.text_range().end();
+    let mut end = start;
+    let mut ends_line = false;
+    let mut next = element.next_sibling_or_token();
+
+    while let Some(current) = next {
+        match current.kind() {
+            SyntaxKind::COMMA if start == end => {
+                start = current.text_range().end();
+                end = start;
+            }
+            SyntaxKind::COMMENT => end = current.text_range().end(),
+            SyntaxKind::WHITESPACE if !current.to_string().contains('\n') => {}
+            kind => {
+                ends_line = kind == SyntaxKind::WHITESPACE;
+                break;
+            }
+        }
+
+        next = current.next_sibling_or_token();
+    }
+
+    let range = TextRange::new(start, end);
+    let text = element
+        .parent()
+        .map(|x| x.text().slice(range - x.text_range().start()).to_string())
+        .unwrap_or_default();
+
+    (range, text, ends_line)
+}
+
+/// Returns the end of the line the element ends on, excluding the line break, along
+/// with whether the element is followed by a comma
+fn line_end(element: &SyntaxElement) -> (TextSize, bool) {
+    let mut end = element.text_range().end();
+    let mut has_comma = false;
+    let mut next = element.next_sibling_or_token();
+
+    while let Some(current) = next {
+        match current.kind() {
+            SyntaxKind::COMMA => has_comma = true,
+            SyntaxKind::COMMENT => {}
+            SyntaxKind::WHITESPACE if !current.to_string().contains('\n') => {}
+            _ => break,
+        }
+
+        end = current.text_range().end();
+        next = current.next_sibling_or_token();
+    }
+
+    (end, has_comma)
+}
+
+/// Returns the number of arguments taken by the receiver when calling a method with UFCS
+pub(crate) fn receiver_args(semantics: &Semantics, call_expr: &ast::CallExpr) -> usize {
+    let path = match call_expr.expr() {
+        Some(ast::Expr::PathExpr(x)) => x.path(),
+        _ => None,
+    };
+
+    match path.and_then(|x| semantics.resolve_path(&x)) {
+        Some(PathResolution::Def(ModuleDef::Function(f)))
+            if f.self_param(semantics.db).is_some() =>
+        {
+            1
+        }
+        _ => 0,
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{
+    ra_ap_syntax::{
+        ast::{self, AstNode},
+        NodeOrToken, SyntaxKind, SyntaxToken,
+    },
+    utils::normalize,
+    Semantics,
+};
+
+use ra_ap_hir::{Crate, Macro, ModuleDef, ScopeDef};
+
+/// Returns the name token of each path listed in a `#[derive(..)]` attribute
+pub(crate) fn derive_names(attr: &ast::Attr) -> Vec<SyntaxToken> {
+    let mut names = vec![];
+
+    if attr.simple_name().as_deref() != Some("derive") {
+        return names;
+    }
+
+    let tokens = attr
+        .token_tree()
+        .into_iter()
+        .flat_map(|x| x.syntax().children_with_tokens())
+        .filter_map(|x| x.into_token());
+
+    let mut last = None;
+
+    for token in tokens {
+        match token.kind() {
+            SyntaxKind::IDENT => last = Some(token),
+            SyntaxKind::COMMA => names.extend(last.take()),
+            _ => {}
+        }
+    }
+
+    names.extend(last);
+    names
+}
+
+/// Returns the macros which can be reached at the path, following re-exports
+pub(crate) fn macros_at(semantics: &Semantics, path: &str) -> Vec<Macro> {
+    let db = semantics.db;
+    let mut segments = path.split("::");
+
+    let (krate, name) = match (segments.next(), segments.next_back()) {
+        (Some(krate), Some(name)) => (normalize(krate), name),
+        _ => return vec![],
+    };
+
+    let mut module = match Crate::all(db).into_iter().find(|x| {
+        x.display_name(db)
+            .is_some_and(|x| normalize(&x.to_string()) == krate)
+    }) {
+        Some(x) => x.root_module(),
+        None => return vec![],
+    };
+
+    for segment in segments {
+        let child = module
+            .scope(db, None)
+            .into_iter()
+            .find_map(|(x, def)| match def {
+                ScopeDef::ModuleDef(ModuleDef::Module(m))
+                    if x.display(db).to_string() == segment =>
+                {
+                    Some(m)
+                }
+                _ => None,
+            });
+
+        module = match child {
+            Some(x) => x,
+            None => return vec![],
+        };
+    }
+
+    module
+        .scope(db, None)
+        .into_iter()
+        .filter_map(|(x, def)| match def {
+            ScopeDef::ModuleDef(ModuleDef::Macro(m)) if x.display(db).to_string() == name => {
+                Some(m)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Collects the name of the attribute and the keys inside it along with their tokens.
+/// Keys are written as `attr::key`, and `attr::key::nested` for the keys of nested lists.
+pub(crate) fn attr_keys(attr: &ast::Attr) -> Vec<(String, SyntaxToken)> {
+    let mut keys = vec![];
+
+    let token = match attr
+        .path()
+        .and_then(|x| x.segment())
+        .and_then(|x| x.name_ref())
+        .and_then(|x| x.ident_token())
+    {
+        Some(x) => x,
+        None => return keys,
+    };
+
+    let name = token.text().to_string();
+    keys.push((name.clone(), token));
+
+    if let Some(token_tree) = attr.token_tree() {
+        collect_keys(&token_tree, &name, &mut keys);
+    }
+
+    keys
+}
+
+fn collect_keys(token_tree: &ast::TokenTree, parent: &str, keys: &mut Vec<(String, SyntaxToken)>) {
+    let mut is_start = true;
+    let mut key: Option<String> = None;
+
+    for child in token_tree.syntax().children_with_tokens() {
+        match child {
+            NodeOrToken::Token(token) => match token.kind() {
+                SyntaxKind::WHITESPACE
+                | SyntaxKind::L_PAREN
+                | SyntaxKind::R_PAREN
+                | SyntaxKind::L_BRACK
+                | SyntaxKind::R_BRACK
+                | SyntaxKind::L_CURLY
+                | SyntaxKind::R_CURLY => {}
+                SyntaxKind::COMMA => {
+                    is_start = true;
+                    key = None;
+                }
+                SyntaxKind::IDENT if is_start => {
+                    let path = format!("{}::{}", parent, token.text());
+
+                    keys.push((path.clone(), token));
+                    key = Some(path);
+                    is_start = false;
+                }
+                _ => {
+                    is_start = false;
+                    key = None;
+                }
+            },
+            NodeOrToken::Node(node) => {
+                if let (Some(nested), Some(key)) = (ast::TokenTree::cast(node), &key) {
+                    collect_keys(&nested, key, keys);
+                }
+
+                is_start = false;
+            }
+        }
+    }
+}
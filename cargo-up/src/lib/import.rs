@@ -0,0 +1,496 @@
+use crate::{
+    args::{append_after, line_indent},
+    ra_ap_syntax::{
+        ast::{self, make, AstNode, HasAttrs, HasName, HasVisibility},
+        SyntaxKind, SyntaxNode, TextRange, TextSize,
+    },
+    relocate::{is_crate_path, render_use_tree, segments, split, use_leaves, UseLeaf},
+    utils::normalize,
+    Semantics, Upgrader,
+};
+
+use ra_ap_hir::{AsAssocItem, ModuleDef, PathResolution, ScopeDef, Trait};
+
+const LOCAL_ROOTS: [&str; 6] = ["crate", "self", "super", "std", "core", "alloc"];
+
+/// Adds the imports asked for by the upgrader to the modules containing their ranges
+pub(crate) fn add_imports(
+    upgrader: &mut Upgrader,
+    semantics: &Semantics,
+    source_file: &SyntaxNode,
+    imports: Vec<(TextRange, String)>,
+) {
+    let mut scopes: Vec<(SyntaxNode, Vec<(TextRange, String)>)> = vec![];
+
+    for (range, path) in imports {
+        let scope = import_scope(source_file, range);
+
+        match scopes.iter_mut().find(|x| x.0 == scope) {
+            Some((_, paths)) if paths.iter().any(|x| x.1 == path) => {}
+            Some((_, paths)) => paths.push((range, path)),
+            None => scopes.push((scope, vec![(range, path)])),
+        }
+    }
+
+    for (scope, paths) in scopes {
+        add_scope_imports(upgrader, semantics, &scope, paths);
+    }
+}
+
+/// Returns the items of the innermost module containing the range
+fn import_scope(source_file: &SyntaxNode, range: TextRange) -> SyntaxNode {
+    if !source_file.text_range().contains_range(range) {
+        return source_file.clone();
+    }
+
+    source_file
+        .covering_element(range)
+        .ancestors()
+        .find(|x| {
+            x.kind() == SyntaxKind::ITEM_LIST
+                && x.parent().is_some_and(|x| x.kind() == SyntaxKind::MODULE)
+        })
+        .unwrap_or_else(|| source_file.clone())
+}
+
+fn add_scope_imports(
+    upgrader: &mut Upgrader,
+    semantics: &Semantics,
+    scope: &SyntaxNode,
+    paths: Vec<(TextRange, String)>,
+) {
+    let all = scope
+        .children()
+        .filter_map(ast::Use::cast)
+        .collect::<Vec<_>>();
+    let leaves = all
+        .iter()
+        .filter_map(|x| use_leaves(&x.use_tree()?))
+        .flatten()
+        .collect::<Vec<_>>();
+
+    // Only private imports without attributes are merged into
+    let uses = all
+        .into_iter()
+        .filter(|x| x.attrs().next().is_none() && x.visibility().is_none())
+        .collect::<Vec<_>>();
+    let grouped = uses.iter().any(|x| {
+        x.syntax()
+            .descendants()
+            .any(|x| x.kind() == SyntaxKind::USE_TREE_LIST)
+    });
+
+    // Single path imports which are turned into groups
+    let mut merged: Vec<(ast::UseTree, Vec<UseLeaf>)> = vec![];
+
+    for (range, path) in paths {
+        let target = split(&path);
+
+        if target.len() < 2 || is_imported(&leaves, &target) || in_scope(semantics, scope, &path) {
+            continue;
+        }
+
+        if let Some(name) = clashing_name(scope, &leaves, &target) {
+            upgrader.warn(
+                range,
+                format!(
+                    "Unable to import `{}`, the name `{}` is already used",
+                    path, name
+                ),
+            );
+            continue;
+        }
+
+        let leaf = UseLeaf {
+            path: target.clone(),
+            rename: None,
+            glob: false,
+        };
+
+        let group = uses
+            .iter()
+            .filter_map(|x| find_group(&x.use_tree()?, &[], &target))
+            .max_by_key(|x| x.0);
+
+        if let Some((depth, last)) = group {
+            append_after(upgrader, &last.syntax().clone().into(), &leaf.render(depth));
+            continue;
+        }
+
+        if grouped {
+            let single = uses.iter().filter_map(|x| x.use_tree()).find(|x| {
+                x.use_tree_list().is_none()
+                    && use_leaves(x)
+                        .is_some_and(|x| x.iter().all(|x| x.path.first() == target.first()))
+            });
+
+            if let Some(tree) = single {
+                match merged.iter_mut().find(|x| x.0 == tree) {
+                    Some((_, leaves)) => leaves.push(leaf),
+                    None => {
+                        let mut leaves = use_leaves(&tree).unwrap_or_default();
+                        leaves.push(leaf);
+                        merged.push((tree, leaves));
+                    }
+                }
+
+                continue;
+            }
+        }
+
+        let (offset, text) = new_import(scope, &uses, &path);
+        upgrader.insert(offset, text);
+    }
+
+    for (tree, leaves) in merged {
+        upgrader.replace(tree.syntax().text_range(), render_use_tree(&leaves));
+    }
+}
+
+/// Whether the path is imported by name or by a glob import of its parent
+fn is_imported(leaves: &[UseLeaf], target: &[String]) -> bool {
+    leaves.iter().any(|x| {
+        if x.glob {
+            x.path[..] == target[..target.len() - 1]
+        } else {
+            x.path[..] == target[..]
+        }
+    })
+}
+
+/// Whether the item of the path is already reachable by its name in the module, which
+/// also covers glob imports of other modules re-exporting it and the prelude
+fn in_scope(semantics: &Semantics, scope: &SyntaxNode, path: &str) -> bool {
+    let scope = match semantics.scope(scope) {
+        Some(x) => x,
+        None => return false,
+    };
+
+    let def = match scope.speculative_resolve(&make::path_from_text(path)) {
+        Some(PathResolution::Def(x)) => x,
+        _ => return false,
+    };
+
+    let name = split(path).pop().unwrap_or_default();
+    let mut found = false;
+
+    scope.process_all_names(&mut |x, scope_def| {
+        if x.to_smol_str() == name && scope_def == ScopeDef::ModuleDef(def) {
+            found = true;
+        }
+    });
+
+    found
+}
+
+/// Returns the name of the path if something else is imported or defined with it
+fn clashing_name(scope: &SyntaxNode, leaves: &[UseLeaf], target: &[String]) -> Option<String> {
+    let name = target.last()?;
+
+    let imported = leaves.iter().any(|x| {
+        let alias = match &x.rename {
+            Some(rename) => rename.trim_start_matches("as").trim(),
+            None => x.path.last().map_or("", |x| x.as_str()),
+        };
+
+        !x.glob && alias == name && x.path[..] != target[..]
+    });
+    let defined = scope
+        .children()
+        .filter_map(ast::AnyHasName::cast)
+        .any(|x| x.name().is_some_and(|x| x.text() == name.as_str()));
+
+    if imported || defined {
+        Some(name.clone())
+    } else {
+        None
+    }
+}
+
+/// Returns the depth and the last use tree of the innermost group in the tree which the
+/// path can be added to
+fn find_group(
+    tree: &ast::UseTree,
+    parent: &[String],
+    target: &[String],
+) -> Option<(usize, ast::UseTree)> {
+    let path = match tree.path() {
+        Some(path) => [parent, &segments(&path)?].concat(),
+        None => parent.to_vec(),
+    };
+    let list = tree.use_tree_list()?;
+
+    if path.len() >= target.len() || target[..path.len()] != path[..] {
+        return None;
+    }
+
+    list.use_trees()
+        .find_map(|x| find_group(&x, &path, target))
+        .or_else(|| Some((path.len(), list.use_trees().last()?)))
+        .filter(|x| x.0 > 0)
+}
+
+/// Returns where to insert a new import of the path along with its text. It goes after the
+/// imports from the same crate, or sorted into the imports of other crates, or at the
+/// start of the module.
+fn new_import(scope: &SyntaxNode, uses: &[ast::Use], path: &str) -> (TextSize, String) {
+    let root = path.split("::").next().unwrap_or_default();
+    let root_of = |x: &ast::Use| {
+        x.use_tree()
+            .and_then(|x| x.path())
+            .and_then(|x| x.first_segment())
+            .and_then(|x| x.name_ref())
+            .map(|x| x.text().to_string())
+            .unwrap_or_default()
+    };
+    let indent_of = |x: &SyntaxNode| line_indent(x.clone().into()).unwrap_or_default();
+
+    if let Some(last) = uses.iter().rev().find(|x| root_of(x) == root) {
+        let indent = indent_of(last.syntax());
+        return (
+            last.syntax().text_range().end(),
+            format!("\n{}use {};", indent, path),
+        );
+    }
+
+    let external = uses
+        .iter()
+        .filter(|x| !LOCAL_ROOTS.contains(&root_of(x).as_str()))
+        .collect::<Vec<_>>();
+
+    if let Some(next) = external.iter().find(|x| root_of(x).as_str() > root) {
+        let indent = indent_of(next.syntax());
+        return (
+            next.syntax().text_range().start(),
+            format!("use {};\n{}", path, indent),
+        );
+    }
+
+    if let Some(last) = external.last().copied().or_else(|| uses.last()) {
+        let indent = indent_of(last.syntax());
+        let separator = if external.is_empty() { "\n" } else { "" };
+
+        return (
+            last.syntax().text_range().end(),
+            format!("\n{}{}use {};", separator, indent, path),
+        );
+    }
+
+    match scope.children().find_map(ast::Item::cast) {
+        Some(item) => {
+            let indent = indent_of(item.syntax());
+            (
+                item.syntax().text_range().start(),
+                format!("use {};\n\n{}", path, indent),
+            )
+        }
+        None => match ast::ItemList::cast(scope.clone()).and_then(|x| x.l_curly_token()) {
+            Some(l_curly) => {
+                let indent = scope.parent().map(|x| indent_of(&x)).unwrap_or_default();
+                (
+                    l_curly.text_range().end(),
+                    format!("\n{}    use {};\n{}", indent, path, indent),
+                )
+            }
+            None => (scope.text_range().end(), format!("use {};\n", path)),
+        },
+    }
+}
+
+/// Removes the imports of the crate which are not used in their scope anymore, collapsing
+/// the groups which are left with fewer paths. Globs, re-exports, imports with attributes and
+/// imports which could be traits used by method calls are kept.
+pub(crate) fn remove_unused_imports(
+    upgrader: &mut Upgrader,
+    semantics: &Semantics,
+    source_file: &SyntaxNode,
+    dep: &str,
+) {
+    let scopes = source_file
+        .descendants()
+        .filter(|x| x.children().any(|x| ast::Use::can_cast(x.kind())));
+
+    for scope in scopes {
+        remove_scope_imports(upgrader, semantics, &scope, dep);
+    }
+}
+
+fn remove_scope_imports(
+    upgrader: &mut Upgrader,
+    semantics: &Semantics,
+    scope: &SyntaxNode,
+    dep: &str,
+) {
+    let uses = scope
+        .children()
+        .filter_map(ast::Use::cast)
+        .collect::<Vec<_>>();
+
+    // Names used in the scope outside its imports, including the ones in macro calls. Only
+    // the nested imports going through the parent modules can use the imports.
+    let imports = scope
+        .descendants()
+        .filter_map(ast::Use::cast)
+        .filter(|x| {
+            !x.syntax().descendants_with_tokens().any(|x| {
+                matches!(
+                    x.kind(),
+                    SyntaxKind::SUPER_KW | SyntaxKind::SELF_KW | SyntaxKind::CRATE_KW
+                )
+            }) || uses.contains(x)
+        })
+        .map(|x| x.syntax().text_range())
+        .collect::<Vec<_>>();
+    let names = scope
+        .descendants_with_tokens()
+        .filter_map(|x| x.into_token())
+        .filter(|x| {
+            x.kind() == SyntaxKind::IDENT
+                && !imports.iter().any(|r| r.contains_range(x.text_range()))
+        })
+        .map(|x| x.text().to_string())
+        .collect::<Vec<_>>();
+
+    let mut traits = None;
+
+    for item in &uses {
+        // Re-exports and conditional imports are not only used by the scope
+        if item.attrs().next().is_some() || item.visibility().is_some() {
+            continue;
+        }
+
+        let tree = match item.use_tree() {
+            Some(x) => x,
+            None => continue,
+        };
+        let leaves = match use_leaves(&tree) {
+            Some(x) => x,
+            None => continue,
+        };
+
+        let keep = leaves
+            .iter()
+            .filter(|leaf| {
+                let is_dep = leaf
+                    .path
+                    .first()
+                    .is_some_and(|x| normalize(x) == normalize(dep))
+                    || tree
+                        .path()
+                        .is_some_and(|x| is_crate_path(semantics, &x, dep));
+
+                if !is_dep || leaf.glob {
+                    return true;
+                }
+
+                let name = match &leaf.rename {
+                    Some(rename) => rename.trim_start_matches("as").trim(),
+                    None => leaf.path.last().map_or("", |x| x.as_str()),
+                };
+
+                if name == "_" || names.iter().any(|x| x == name) {
+                    return true;
+                }
+
+                let (used, unresolved) =
+                    traits.get_or_insert_with(|| used_traits(semantics, scope));
+
+                match resolve(semantics, scope, &leaf.path) {
+                    Some(ModuleDef::Trait(x)) => used.contains(&x),
+                    Some(_) => false,
+                    None => *unresolved,
+                }
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if keep.is_empty() {
+            upgrader.delete(line_range(item.syntax()));
+        } else if keep.len() != leaves.len() {
+            upgrader.replace(tree.syntax().text_range(), render_use_tree(&keep));
+        }
+    }
+}
+
+fn resolve(semantics: &Semantics, scope: &SyntaxNode, path: &[String]) -> Option<ModuleDef> {
+    let path = make::path_from_text(&path.join("::"));
+
+    match semantics.scope(scope)?.speculative_resolve(&path)? {
+        PathResolution::Def(x) => Some(x),
+        _ => None,
+    }
+}
+
+/// Returns the traits whose methods are called in the scope, and whether there are calls
+/// which might be using a trait but could not be resolved
+fn used_traits(semantics: &Semantics, scope: &SyntaxNode) -> (Vec<Trait>, bool) {
+    let mut traits = vec![];
+    let mut unresolved = false;
+
+    for node in scope.descendants() {
+        let function = if let Some(x) = ast::MethodCallExpr::cast(node.clone()) {
+            semantics.resolve_method_call(&x)
+        } else if let Some(x) = ast::PathExpr::cast(node.clone()) {
+            match x.path() {
+                Some(path) if path.qualifier().is_some() => match semantics.resolve_path(&path) {
+                    Some(PathResolution::Def(ModuleDef::Function(x))) => Some(x),
+                    Some(_) => continue,
+                    None => None,
+                },
+                _ => continue,
+            }
+        } else if ast::MacroCall::can_cast(node.kind()) {
+            // Method calls in the arguments of macros are not parsed
+            None
+        } else {
+            continue;
+        };
+
+        match function {
+            Some(x) => traits.extend(
+                x.as_assoc_item(semantics.db)
+                    .and_then(|x| x.container_or_implemented_trait(semantics.db)),
+            ),
+            None => unresolved = true,
+        }
+    }
+
+    (traits, unresolved)
+}
+
+/// Range of the import along with its line, and the blank line after it when nothing but
+/// the start of the scope or another blank line is before it
+fn line_range(node: &SyntaxNode) -> TextRange {
+    let range = node.text_range();
+    let root = node.ancestors().last().unwrap_or_else(|| node.clone());
+    let text = root.to_string();
+
+    let (start, end): (usize, usize) = (range.start().into(), range.end().into());
+    let line_start = text[..start].rfind('\n').map_or(0, |x| x + 1);
+    let line_end = text[end..].find('\n').map(|x| end + x + 1);
+
+    let (start, end) = match line_end {
+        Some(line_end)
+            if text[line_start..start].trim().is_empty()
+                && text[end..line_end].trim().is_empty() =>
+        {
+            let before = text[..line_start].trim_end_matches([' ', '\t']);
+            let after = text[line_end..].find('\n').map(|x| line_end + x + 1);
+
+            match after {
+                Some(after)
+                    if text[line_end..after].trim().is_empty()
+                        && (before.is_empty()
+                            || before.ends_with("{\n")
+                            || before.ends_with("\n\n")) =>
+                {
+                    (line_start, after)
+                }
+                _ => (line_start, line_end),
+            }
+        }
+        _ => (start, end),
+    };
+
+    TextRange::new(TextSize::from(start as u32), TextSize::from(end as u32))
+}
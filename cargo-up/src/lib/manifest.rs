@@ -0,0 +1,349 @@
+use crate::{
+    semver::{Comparator, Op, Prerelease, Version as SemverVersion, VersionReq},
+    utils::normalize,
+};
+
+use toml_edit::{Array, DocumentMut, InlineTable, Item, TableLike, TomlError, Value};
+
+const DEP_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+#[derive(Debug, Clone)]
+pub(crate) enum ManifestEdit {
+    AddDep { name: String, version: String },
+    RemoveDep { name: String },
+    AddFeature { dep: String, feature: String },
+    RemoveFeature { dep: String, feature: String },
+}
+
+/// Applies the edits to the dependency tables declaring `dep` and bumps its requirement to `to`.
+/// Returns the new manifest text, with the formatting and comments of the original preserved.
+pub(crate) fn edit_manifest(
+    text: &str,
+    dep: &str,
+    to: &SemverVersion,
+    edits: &[ManifestEdit],
+) -> Result<String, TomlError> {
+    let mut doc = text.parse::<DocumentMut>()?;
+
+    for (is_workspace, table) in dep_tables(&mut doc) {
+        let declares_dep = find_key(table, dep).is_some();
+
+        for edit in edits {
+            match edit {
+                ManifestEdit::AddDep { name, version } => {
+                    if declares_dep && !is_workspace && find_key(table, name).is_none() {
+                        table.insert(name, Item::Value(Value::from(version.as_str())));
+                    }
+                }
+                ManifestEdit::RemoveDep { name } => {
+                    if !is_workspace {
+                        if let Some(key) = find_key(table, name) {
+                            table.remove(&key);
+                        }
+                    }
+                }
+                ManifestEdit::AddFeature { dep: name, feature } => {
+                    if let Some(features) = find_key(table, name)
+                        .and_then(|key| table.get_mut(&key))
+                        .and_then(features_mut)
+                    {
+                        if !features.iter().any(|x| x.as_str() == Some(feature)) {
+                            features.push(feature.as_str());
+                        }
+                    }
+                }
+                ManifestEdit::RemoveFeature { dep: name, feature } => {
+                    if let Some(features) = find_key(table, name)
+                        .and_then(|key| table.get_mut(&key))
+                        .and_then(|x| x.get_mut("features"))
+                        .and_then(|x| x.as_array_mut())
+                    {
+                        features.retain(|x| x.as_str() != Some(feature));
+                    }
+                }
+            }
+        }
+
+        if let Some(item) = find_key(table, dep).and_then(|key| table.get_mut(&key)) {
+            bump_version(item, to);
+        }
+    }
+
+    Ok(doc.to_string())
+}
+
+/// Collects every dependency table of the manifest along with whether it is the
+/// `[workspace.dependencies]` table.
+fn dep_tables(doc: &mut DocumentMut) -> Vec<(bool, &mut dyn TableLike)> {
+    let mut tables = vec![];
+
+    for (key, item) in doc.as_table_mut().iter_mut() {
+        match key.get() {
+            "target" => {
+                let targets = match item.as_table_like_mut() {
+                    Some(x) => x,
+                    None => continue,
+                };
+
+                for (_, target) in targets.iter_mut() {
+                    if let Some(target) = target.as_table_like_mut() {
+                        for (key, item) in target.iter_mut() {
+                            if DEP_TABLES.contains(&key.get()) {
+                                if let Some(table) = item.as_table_like_mut() {
+                                    tables.push((false, table));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            "workspace" => {
+                if let Some(table) = item
+                    .get_mut("dependencies")
+                    .and_then(|x| x.as_table_like_mut())
+                {
+                    tables.push((true, table));
+                }
+            }
+            key if DEP_TABLES.contains(&key) => {
+                if let Some(table) = item.as_table_like_mut() {
+                    tables.push((false, table));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    tables
+}
+
+fn find_key(table: &dyn TableLike, name: &str) -> Option<String> {
+    let name = normalize(name);
+
+    table
+        .iter()
+        .find(|(key, _)| normalize(key) == name)
+        .map(|(key, _)| key.to_string())
+}
+
+/// Returns the features array of a dependency, converting `dep = "1.0"` into an inline
+/// table if needed.
+fn features_mut(item: &mut Item) -> Option<&mut Array> {
+    if let Some(version) = item.as_str() {
+        let mut table = InlineTable::new();
+        table.insert("version", Value::from(version));
+
+        let decor = item.as_value().map(|x| x.decor().clone());
+        let mut value = Value::InlineTable(table);
+
+        if let Some(decor) = decor {
+            *value.decor_mut() = decor;
+        }
+
+        *item = Item::Value(value);
+    }
+
+    let table = item.as_table_like_mut()?;
+
+    if !table.contains_key("features") {
+        table.insert("features", Item::Value(Value::Array(Array::new())));
+        table.fmt();
+    }
+
+    table.get_mut("features")?.as_array_mut()
+}
+
+/// Bumps the requirement of the dependency so that it allows `to`, keeping its operators
+/// and the number of version components they were written with
+fn bump_version(item: &mut Item, to: &SemverVersion) {
+    let value = match item {
+        Item::Value(Value::String(_)) => item.as_value_mut(),
+        _ => item
+            .as_table_like_mut()
+            .and_then(|x| x.get_mut("version"))
+            .and_then(|x| x.as_value_mut()),
+    };
+
+    let value = match value {
+        Some(x) => x,
+        None => return,
+    };
+
+    let req = match value.as_str() {
+        Some(x) => x.trim().to_string(),
+        None => return,
+    };
+
+    let parsed = match VersionReq::parse(&req) {
+        Ok(x) => x,
+        Err(_) => return,
+    };
+
+    if parsed.matches(to) {
+        return;
+    }
+
+    // A bare requirement stays bare, others keep the operator of each of their comparators
+    let is_bare = req.starts_with(|x: char| x.is_ascii_digit())
+        && parsed.comparators.len() == 1
+        && parsed.comparators[0].op == Op::Caret;
+
+    let bumped = parsed
+        .comparators
+        .iter()
+        .map(|x| bump_comparator(x, to).to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let req = if is_bare {
+        bumped.trim_start_matches('^').to_string()
+    } else {
+        bumped
+    };
+
+    let decor = value.decor().clone();
+    *value = Value::from(req);
+    *value.decor_mut() = decor;
+}
+
+/// Rewrites the version of the comparator when it does not allow `to`, keeping its operator
+/// and its precision
+fn bump_comparator(comparator: &Comparator, to: &SemverVersion) -> Comparator {
+    if comparator.matches(to) {
+        return comparator.clone();
+    }
+
+    // Pre-releases are only allowed by comparators naming their exact version
+    let precision = if to.pre.is_empty() {
+        1 + comparator.minor.iter().count() + comparator.patch.iter().count()
+    } else {
+        3
+    };
+
+    // Components needed to name the breaking version `to` is in
+    let breaking = if to.major > 0 {
+        1
+    } else if to.minor > 0 {
+        2
+    } else {
+        3
+    };
+
+    let (op, version, precision) = match comparator.op {
+        // Upper bounds move to the end of the breaking version of `to`
+        Op::Less => {
+            let next = if to.major > 0 {
+                SemverVersion::new(to.major + 1, 0, 0)
+            } else if to.minor > 0 {
+                SemverVersion::new(0, to.minor + 1, 0)
+            } else {
+                SemverVersion::new(0, 0, to.patch + 1)
+            };
+
+            (Op::Less, next, precision.max(breaking))
+        }
+        Op::LessEq if to.pre.is_empty() => (Op::LessEq, to.clone(), breaking),
+        // A lower bound above `to` can only be lowered to it
+        Op::Greater => (Op::GreaterEq, to.clone(), precision),
+        // Wildcards can not name a patch version
+        Op::Wildcard => (Op::Wildcard, to.clone(), precision.min(2)),
+        op => (op, to.clone(), precision),
+    };
+
+    Comparator {
+        op,
+        major: version.major,
+        minor: Some(version.minor).filter(|_| precision > 1),
+        patch: Some(version.patch).filter(|_| precision > 2),
+        pre: if precision > 2 {
+            version.pre
+        } else {
+            Prerelease::EMPTY
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bump(manifest: &str, to: &str) -> String {
+        edit_manifest(
+            manifest,
+            "upgradee",
+            &SemverVersion::parse(to).unwrap(),
+            &[],
+        )
+        .unwrap()
+    }
+
+    fn bump_req(req: &str, to: &str) -> String {
+        let manifest = bump(&format!("[dependencies]\nupgradee = \"{}\"\n", req), to);
+
+        manifest
+            .trim_start_matches("[dependencies]\nupgradee = \"")
+            .trim_end_matches("\"\n")
+            .to_string()
+    }
+
+    #[test]
+    fn test_bump_caret() {
+        assert_eq!(bump_req("0.2", "0.3.1"), "0.3");
+        assert_eq!(bump_req("0.2.1", "0.3.1"), "0.3.1");
+        assert_eq!(bump_req("^1", "2.0.3"), "^2");
+        assert_eq!(bump_req("^0.2.1", "0.3.1"), "^0.3.1");
+        assert_eq!(bump_req("0.2", "0.2.5"), "0.2");
+    }
+
+    #[test]
+    fn test_bump_tilde() {
+        assert_eq!(bump_req("~1", "2.0.3"), "~2");
+        assert_eq!(bump_req("~1.2", "2.0.3"), "~2.0");
+        assert_eq!(bump_req("~1.2.3", "2.0.3"), "~2.0.3");
+    }
+
+    #[test]
+    fn test_bump_wildcard() {
+        assert_eq!(bump_req("1.*", "2.0.3"), "2.*");
+        assert_eq!(bump_req("0.2.*", "0.3.1"), "0.3.*");
+    }
+
+    #[test]
+    fn test_bump_pre_release() {
+        assert_eq!(bump_req("1", "2.0.0-rc.1"), "2.0.0-rc.1");
+        assert_eq!(bump_req("~1.2", "2.0.0-rc.1"), "~2.0.0-rc.1");
+    }
+
+    #[test]
+    fn test_bump_bounded_range() {
+        assert_eq!(bump_req(">=0.2, <0.3", "0.3.1"), ">=0.2, <0.4");
+        assert_eq!(bump_req(">=1, <2", "2.1.0"), ">=1, <3");
+        assert_eq!(bump_req(">=1.0.0, <2.0.0", "2.1.0"), ">=1.0.0, <3.0.0");
+        assert_eq!(bump_req(">=0.2, <=0.2.4", "0.3.1"), ">=0.2, <=0.3");
+        assert_eq!(bump_req(">=1.0.0, <=1.4.2", "2.1.0"), ">=1.0.0, <=2");
+        assert_eq!(bump_req(">3", "2.0.0"), ">=2");
+    }
+
+    #[test]
+    fn test_bump_inline_table() {
+        assert_eq!(
+            bump(
+                "[dependencies]\nupgradee = { version = \"~0.2\", features = [\"full\"] } # pinned\n",
+                "0.3.1"
+            ),
+            "[dependencies]\nupgradee = { version = \"~0.3\", features = [\"full\"] } # pinned\n"
+        );
+    }
+
+    #[test]
+    fn test_bump_workspace_inherited() {
+        let manifest = "[workspace.dependencies]\nupgradee = \"0.2\"\n\n\
+                        [dependencies]\nupgradee = { workspace = true }\n";
+
+        assert_eq!(
+            bump(manifest, "0.3.1"),
+            "[workspace.dependencies]\nupgradee = \"0.3\"\n\n\
+             [dependencies]\nupgradee = { workspace = true }\n"
+        );
+    }
+}
@@ -6,12 +6,17 @@ pub use semver;
 
 pub type Semantics<'db> = ra_ap_hir::Semantics<'db, ra_ap_ide_db::RootDatabase>;
 
+mod args;
+mod attrs;
+mod import;
+mod manifest;
 mod preloader;
+mod relocate;
 mod runner;
 mod upgrader;
 mod utils;
 mod version;
 
-pub use runner::{run, Runner};
+pub use runner::{run_range, Format, RunOptions, Runner};
 pub use upgrader::Upgrader;
 pub use version::Version;
@@ -1,102 +1,668 @@
-use log::trace;
+use crate::utils::normalize;
+
+use log::{debug, trace};
 use oclif::term::TERM_ERR;
-use ra_ap_hir::{Adt, AssocItem, Crate, Field, Function, Module, ModuleDef, Struct, Variant};
+use ra_ap_base_db::SourceDatabaseExt;
+use ra_ap_hir::{
+    Adt, AsAssocItem, AssocItem, AssocItemContainer, Const, Crate, Enum, Field, Function, Macro,
+    Module, ModuleDef, Name, ScopeDef, Static, Struct, Trait, TypeAlias, Union, Variant,
+    VariantDef,
+};
 use ra_ap_ide_db::RootDatabase;
 
-use std::collections::HashMap as Map;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap as Map, HashSet as Set},
+    fs::{create_dir_all, read_to_string, write},
+    hash::{Hash, Hasher},
+    io,
+    ops::{Deref, DerefMut},
+    path::Path,
+};
+
+/// Full paths the items can be reached at, ending with the name they are exported as. Items of
+/// the crates loaded from the cache are only known by the path they are defined at until they
+/// are looked up.
+#[derive(Debug)]
+pub(crate) struct Table<I> {
+    items: Map<I, Vec<String>>,
+    cached: Map<String, Vec<String>>,
+}
+
+impl<I> Default for Table<I> {
+    fn default() -> Self {
+        Self {
+            items: Map::new(),
+            cached: Map::new(),
+        }
+    }
+}
+
+impl<I> Deref for Table<I> {
+    type Target = Map<I, Vec<String>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.items
+    }
+}
+
+impl<I> DerefMut for Table<I> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.items
+    }
+}
+
+impl<I: Named> Table<I> {
+    /// Returns the paths the item can be reached at
+    pub(crate) fn paths_of(&self, db: &RootDatabase, item: &I) -> Option<Vec<String>> {
+        let mut paths = self.items.get(item).cloned();
+
+        if self.cached.is_empty() {
+            return paths;
+        }
+
+        if let Some(cached) = item.def_path(db).and_then(|x| self.cached.get(&x)) {
+            let paths = paths.get_or_insert_with(Vec::new);
+
+            for path in cached {
+                if !paths.contains(path) {
+                    paths.push(path.clone());
+                }
+            }
+        }
+
+        paths
+    }
+}
+
+impl Table<Trait> {
+    /// Returns the traits which can be reached at the path
+    pub(crate) fn at(&self, db: &RootDatabase, path: &str) -> Vec<Trait> {
+        let mut traits = self
+            .items
+            .iter()
+            .filter(|(_, paths)| paths.iter().any(|x| x == path))
+            .map(|(t, _)| *t)
+            .collect::<Vec<_>>();
+
+        for (def_path, paths) in &self.cached {
+            if paths.iter().any(|x| x == path) {
+                if let Some(ModuleDef::Trait(t)) = resolve(db, def_path) {
+                    if !traits.contains(&t) {
+                        traits.push(t);
+                    }
+                }
+            }
+        }
+
+        traits
+    }
+}
 
 #[derive(Debug, Default)]
 pub(crate) struct Preloader {
-    pub(crate) methods: Map<Function, String>,
-    pub(crate) structs: Map<Struct, String>,
-    pub(crate) members: Map<Field, String>,
-    pub(crate) variants: Map<Variant, String>,
+    pub(crate) functions: Table<Function>,
+    pub(crate) methods: Table<Function>,
+    pub(crate) structs: Table<Struct>,
+    pub(crate) enums: Table<Enum>,
+    pub(crate) unions: Table<Union>,
+    pub(crate) type_aliases: Table<TypeAlias>,
+    pub(crate) members: Table<Field>,
+    pub(crate) variants: Table<Variant>,
+    pub(crate) consts: Table<Const>,
+    pub(crate) statics: Table<Static>,
+    pub(crate) macros: Table<Macro>,
+    pub(crate) traits: Table<Trait>,
+    pub(crate) trait_methods: Table<Function>,
+    /// Every module, item and associated item path of the loaded crates
+    pub(crate) paths: Set<String>,
     pub(crate) visited: Vec<String>,
 }
 
+/// Records one more path the item can be reached at, the first one being where it is defined
+fn add<I: Eq + Hash>(map: &mut Map<I, Vec<String>>, item: I, path: String) {
+    let paths = map.entry(item).or_default();
+
+    if !paths.contains(&path) {
+        paths.push(path);
+    }
+}
+
+/// Records the item under its own name inside the parent
+fn add_in<I: Named>(map: &mut Map<I, Vec<String>>, db: &RootDatabase, item: I, parent: &str) {
+    if let Some(name) = item.item_name(db) {
+        let path = format!("{}::{}", parent, name.display(db));
+        add(map, item, path);
+    }
+}
+
+/// Items which are reached by their name under the path of their parent
+pub(crate) trait Named: Eq + Hash {
+    fn item_name(&self, db: &RootDatabase) -> Option<Name>;
+
+    /// Path of the module, type or trait the item is defined in
+    fn parent_path(&self, db: &RootDatabase) -> Option<String>;
+
+    /// Path the item is defined at, which is what it is cached by
+    fn def_path(&self, db: &RootDatabase) -> Option<String> {
+        Some(format!(
+            "{}::{}",
+            self.parent_path(db)?,
+            self.item_name(db)?.display(db)
+        ))
+    }
+}
+
+macro_rules! named {
+    ($($item:ident,)*) => {
+        $(
+            impl Named for $item {
+                fn item_name(&self, db: &RootDatabase) -> Option<Name> {
+                    Some(self.name(db))
+                }
+
+                fn parent_path(&self, db: &RootDatabase) -> Option<String> {
+                    module_path(db, self.module(db))
+                }
+            }
+        )*
+    };
+}
+
+named!(Struct, Enum, Union, TypeAlias, Static, Trait,);
+
+impl Named for Function {
+    fn item_name(&self, db: &RootDatabase) -> Option<Name> {
+        Some(self.name(db))
+    }
+
+    fn parent_path(&self, db: &RootDatabase) -> Option<String> {
+        match self.as_assoc_item(db) {
+            Some(item) => assoc_parent_path(db, item),
+            None => module_path(db, self.module(db)),
+        }
+    }
+}
+
+impl Named for Const {
+    fn item_name(&self, db: &RootDatabase) -> Option<Name> {
+        self.name(db)
+    }
+
+    fn parent_path(&self, db: &RootDatabase) -> Option<String> {
+        match self.as_assoc_item(db) {
+            Some(item) => assoc_parent_path(db, item),
+            None => module_path(db, self.module(db)),
+        }
+    }
+}
+
+impl Named for Field {
+    fn item_name(&self, db: &RootDatabase) -> Option<Name> {
+        Some(self.name(db))
+    }
+
+    fn parent_path(&self, db: &RootDatabase) -> Option<String> {
+        match self.parent_def(db) {
+            VariantDef::Struct(s) => s.def_path(db),
+            VariantDef::Union(u) => u.def_path(db),
+            VariantDef::Variant(_) => None,
+        }
+    }
+}
+
+impl Named for Variant {
+    fn item_name(&self, db: &RootDatabase) -> Option<Name> {
+        Some(self.name(db))
+    }
+
+    fn parent_path(&self, db: &RootDatabase) -> Option<String> {
+        self.parent_enum(db).def_path(db)
+    }
+}
+
+impl Named for Macro {
+    fn item_name(&self, db: &RootDatabase) -> Option<Name> {
+        Some(self.name(db))
+    }
+
+    // Exported macros live at the crate root
+    fn parent_path(&self, db: &RootDatabase) -> Option<String> {
+        if self.is_macro_export(db) {
+            module_path(db, self.module(db).krate().root_module())
+        } else {
+            module_path(db, self.module(db))
+        }
+    }
+}
+
+/// Returns the path of the module, starting with the name of its crate
+fn module_path(db: &RootDatabase, module: Module) -> Option<String> {
+    let mut path = vec![normalize(&module.krate().display_name(db)?.to_string())];
+
+    for module in module.path_to_root(db).into_iter().rev() {
+        path.extend(module.name(db).map(|x| x.display(db).to_string()));
+    }
+
+    Some(path.join("::"))
+}
+
+/// Returns the path of the trait or of the type whose inherent impl the item is in
+fn assoc_parent_path(db: &RootDatabase, item: AssocItem) -> Option<String> {
+    match item.container(db) {
+        AssocItemContainer::Trait(t) => t.def_path(db),
+        AssocItemContainer::Impl(i) if i.trait_(db).is_none() => match i.self_ty(db).as_adt()? {
+            Adt::Struct(s) => s.def_path(db),
+            Adt::Enum(e) => e.def_path(db),
+            Adt::Union(u) => u.def_path(db),
+        },
+        AssocItemContainer::Impl(_) => None,
+    }
+}
+
+/// Resolves the module item defined at the path
+fn resolve(db: &RootDatabase, path: &str) -> Option<ModuleDef> {
+    let mut segments = path.split("::");
+    let krate = segments.next()?;
+    let name = segments.next_back()?;
+
+    let mut module = Crate::all(db)
+        .into_iter()
+        .find(|x| {
+            x.display_name(db)
+                .is_some_and(|x| normalize(&x.to_string()) == krate)
+        })?
+        .root_module();
+
+    for segment in segments {
+        module = module.children(db).find(|x| {
+            x.name(db)
+                .is_some_and(|x| x.display(db).to_string() == segment)
+        })?;
+    }
+
+    module.declarations(db).into_iter().find(|x| {
+        x.name(db)
+            .is_some_and(|x| x.display(db).to_string() == name)
+    })
+}
+
+/// Records every path the items can be reached at
+fn add_paths<I>(paths: &mut Set<String>, map: &Map<I, Vec<String>>) {
+    paths.extend(map.values().flatten().cloned());
+}
+
+/// Returns whether the path is inside the crate
+fn is_in(path: &str, krate: &str) -> bool {
+    path == krate || path.starts_with(&format!("{}::", krate))
+}
+
+/// Returns the name of the file the index of the crate is cached in. Crates from a registry
+/// are immutable for a version, while the sources of the others can change in place.
+fn cache_key(name: &str, db: &RootDatabase, krate: &Crate, registry: bool) -> String {
+    let mut hasher = DefaultHasher::new();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+
+    let mut features = krate
+        .cfg(db)
+        .get_cfg_values("feature")
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>();
+    features.sort();
+    features.hash(&mut hasher);
+
+    if !registry {
+        let source_root = db.source_root(db.file_source_root(krate.root_file(db)));
+        let mut files = source_root
+            .iter()
+            .map(|x| {
+                let mut file_hasher = DefaultHasher::new();
+                db.file_text(x).hash(&mut file_hasher);
+                file_hasher.finish()
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+        files.hash(&mut hasher);
+    }
+
+    format!(
+        "{}-{}-{:016x}",
+        name,
+        krate.version(db).unwrap_or_default(),
+        hasher.finish()
+    )
+}
+
+/// Writes the paths of the items which can be reached inside the crate, keyed by the table
+/// and the path the item is defined at
+fn save_table<I: Named>(
+    lines: &mut Vec<String>,
+    table: &str,
+    map: &Map<I, Vec<String>>,
+    krate: &str,
+    db: &RootDatabase,
+) {
+    for (item, paths) in map {
+        if let Some(def_path) = item.def_path(db) {
+            if paths.iter().any(|x| is_in(x, krate)) {
+                lines.push(format!("{}\t{}\t{}", table, def_path, paths.join("\t")));
+            }
+        }
+    }
+}
+
+/// Adds the cached paths of the item to the table
+fn restore_table<I>(table: &mut Table<I>, def_path: &str, paths: Vec<&str>) {
+    let cached = table.cached.entry(def_path.to_string()).or_default();
+
+    for path in paths {
+        if !cached.iter().any(|x| x == path) {
+            cached.push(path.to_string());
+        }
+    }
+}
+
 impl Preloader {
-    pub(crate) fn load(&mut self, name: &str, db: &RootDatabase, krate: &Crate) {
+    pub(crate) fn load(
+        &mut self,
+        name: &str,
+        db: &RootDatabase,
+        krate: &Crate,
+        registry: bool,
+        cache: Option<&Path>,
+    ) {
         if self.visited.iter().any(|x| x == name) {
             return;
         }
 
+        let cache_file = cache.map(|x| x.join(cache_key(name, db, krate, registry)));
+
+        if let Some(text) = cache_file.as_ref().and_then(|x| read_to_string(x).ok()) {
+            debug!("Using cached index of: {}", name);
+
+            self.restore(&text);
+            self.visited.push(name.to_string());
+            return;
+        }
+
         eprintln!("Preloading {} ... ", name);
         TERM_ERR.flush().unwrap();
 
         let module = krate.root_module();
         self.load_module(db, &module, vec![name.to_string()]);
 
+        // Public paths are only the ones visible from outside the crate
+        let outside = Crate::all(db)
+            .into_iter()
+            .find(|x| x != krate)
+            .map(|x| x.root_module());
+        let mut aliases = vec![];
+
+        self.load_reexports(
+            db,
+            &module,
+            vec![name.to_string()],
+            outside,
+            &mut vec![],
+            &mut aliases,
+        );
+
+        // Members of re-exported types and traits can also be reached through their public path
+        for (original, public) in aliases {
+            for paths in self
+                .members
+                .values_mut()
+                .chain(self.variants.values_mut())
+                .chain(self.methods.values_mut())
+                .chain(self.consts.values_mut())
+                .chain(self.trait_methods.values_mut())
+            {
+                let aliased = paths
+                    .iter()
+                    .filter_map(|x| x.strip_prefix(&format!("{}::", original)))
+                    .map(|x| format!("{}::{}", public, x))
+                    .collect::<Vec<_>>();
+
+                for path in aliased {
+                    if !paths.contains(&path) {
+                        paths.push(path);
+                    }
+                }
+            }
+        }
+
+        // Associated items can be reached under every path of their parent
+        add_paths(&mut self.paths, &self.members);
+        add_paths(&mut self.paths, &self.variants);
+        add_paths(&mut self.paths, &self.methods);
+        add_paths(&mut self.paths, &self.consts);
+        add_paths(&mut self.paths, &self.trait_methods);
+
+        if let Some(file) = cache_file {
+            if let Err(err) = self.save(db, name, &file) {
+                debug!("Unable to cache index of {}: {}", name, err);
+            }
+        }
+
         self.visited.push(name.to_string());
         eprintln!("Preloading done");
 
         trace!("{:#?}", self);
     }
 
+    /// Writes the index of the crate to the cache file
+    fn save(&self, db: &RootDatabase, name: &str, file: &Path) -> io::Result<()> {
+        let mut lines = vec![];
+
+        save_table(&mut lines, "functions", &self.functions, name, db);
+        save_table(&mut lines, "methods", &self.methods, name, db);
+        save_table(&mut lines, "structs", &self.structs, name, db);
+        save_table(&mut lines, "enums", &self.enums, name, db);
+        save_table(&mut lines, "unions", &self.unions, name, db);
+        save_table(&mut lines, "type_aliases", &self.type_aliases, name, db);
+        save_table(&mut lines, "members", &self.members, name, db);
+        save_table(&mut lines, "variants", &self.variants, name, db);
+        save_table(&mut lines, "consts", &self.consts, name, db);
+        save_table(&mut lines, "statics", &self.statics, name, db);
+        save_table(&mut lines, "macros", &self.macros, name, db);
+        save_table(&mut lines, "traits", &self.traits, name, db);
+        save_table(&mut lines, "trait_methods", &self.trait_methods, name, db);
+
+        for path in self.paths.iter().filter(|x| is_in(x, name)) {
+            lines.push(format!("paths\t{}", path));
+        }
+
+        lines.sort();
+
+        if let Some(dir) = file.parent() {
+            create_dir_all(dir)?;
+        }
+
+        write(file, lines.join("\n"))
+    }
+
+    /// Loads the index of the crate from the cache file, without walking its declarations
+    fn restore(&mut self, text: &str) {
+        for line in text.lines() {
+            let mut parts = line.split('\t');
+
+            let (table, key) = match (parts.next(), parts.next()) {
+                (Some(table), Some(key)) => (table, key),
+                _ => continue,
+            };
+
+            let paths = parts.collect();
+
+            match table {
+                "paths" => {
+                    self.paths.insert(key.to_string());
+                }
+                "functions" => restore_table(&mut self.functions, key, paths),
+                "methods" => restore_table(&mut self.methods, key, paths),
+                "structs" => restore_table(&mut self.structs, key, paths),
+                "enums" => restore_table(&mut self.enums, key, paths),
+                "unions" => restore_table(&mut self.unions, key, paths),
+                "type_aliases" => restore_table(&mut self.type_aliases, key, paths),
+                "members" => restore_table(&mut self.members, key, paths),
+                "variants" => restore_table(&mut self.variants, key, paths),
+                "consts" => restore_table(&mut self.consts, key, paths),
+                "statics" => restore_table(&mut self.statics, key, paths),
+                "macros" => restore_table(&mut self.macros, key, paths),
+                "traits" => restore_table(&mut self.traits, key, paths),
+                "trait_methods" => restore_table(&mut self.trait_methods, key, paths),
+                _ => {}
+            }
+        }
+    }
+
     fn load_module(&mut self, db: &RootDatabase, module: &Module, path: Vec<String>) {
+        self.paths.insert(path.join("::"));
+
         for def in module.declarations(db) {
+            if let (Some(name), false) = (def.name(db), matches!(def, ModuleDef::Macro(_))) {
+                self.paths
+                    .insert(format!("{}::{}", path.join("::"), name.display(db)));
+            }
+
             match def {
+                // Load free functions
+                ModuleDef::Function(f) => {
+                    add_in(&mut self.functions, db, f, &path.join("::"));
+                }
                 // Load struct && members
                 ModuleDef::Adt(Adt::Struct(s)) => {
-                    self.structs.insert(s.clone(), path.join("::"));
+                    add_in(&mut self.structs, db, s.clone(), &path.join("::"));
 
                     let name = format!("{}::{}", path.join("::"), s.name(db).display(db));
 
                     for field in s.fields(db) {
-                        self.members.insert(field, name.clone());
+                        add_in(&mut self.members, db, field, &name);
                     }
                 }
-                // Load union memebrs
+                // Load union && memebrs
                 ModuleDef::Adt(Adt::Union(u)) => {
+                    add_in(&mut self.unions, db, u, &path.join("::"));
+
                     let name = format!("{}::{}", path.join("::"), u.name(db).display(db));
 
                     for field in u.fields(db) {
-                        self.members.insert(field, name.clone());
+                        add_in(&mut self.members, db, field, &name);
                     }
                 }
-                // Load enum variants
+                // Load enum && variants
                 ModuleDef::Adt(Adt::Enum(e)) => {
+                    add_in(&mut self.enums, db, e, &path.join("::"));
+
                     let name = format!("{}::{}", path.join("::"), e.name(db).display(db));
 
                     for variant in e.variants(db) {
-                        self.variants.insert(variant, name.clone());
+                        add_in(&mut self.variants, db, variant, &name);
+                    }
+                }
+                // Load constants
+                ModuleDef::Const(c) => {
+                    add_in(&mut self.consts, db, c, &path.join("::"));
+                }
+                // Load statics
+                ModuleDef::Static(s) => {
+                    add_in(&mut self.statics, db, s, &path.join("::"));
+                }
+                // Load macros, exported ones live at the crate root
+                ModuleDef::Macro(m) => {
+                    let name = if m.is_macro_export(db) {
+                        path[0].clone()
+                    } else {
+                        path.join("::")
+                    };
+
+                    self.paths
+                        .insert(format!("{}::{}", name, m.name(db).display(db)));
+                    add_in(&mut self.macros, db, m, &name);
+                }
+                // Load type aliases
+                ModuleDef::TypeAlias(t) => {
+                    add_in(&mut self.type_aliases, db, t, &path.join("::"));
+                }
+                // Load traits && trait methods && associated constants
+                ModuleDef::Trait(t) => {
+                    add_in(&mut self.traits, db, t, &path.join("::"));
+
+                    let name = format!("{}::{}", path.join("::"), t.name(db).display(db));
+
+                    for assoc_item in t.items(db) {
+                        match assoc_item {
+                            AssocItem::Function(f) => {
+                                add_in(&mut self.trait_methods, db, f, &name);
+                            }
+                            AssocItem::Const(c) => {
+                                add_in(&mut self.consts, db, c, &name);
+                            }
+                            _ => {}
+                        }
                     }
                 }
                 _ => {}
             }
         }
 
+        // Load exported `macro_rules!` macros
+        for m in module.legacy_macros(db) {
+            if m.is_macro_export(db) {
+                add_in(&mut self.macros, db, m, &path[0]);
+                self.paths
+                    .insert(format!("{}::{}", path[0], m.name(db).display(db)));
+            }
+        }
+
         for impl_def in module.impl_defs(db) {
             let target_ty = impl_def.self_ty(db);
             let target_trait = impl_def.trait_(db);
 
             match target_ty.as_adt() {
-                // Load struct instance methods
+                // Load struct instance methods && associated constants
                 Some(Adt::Struct(s)) if target_trait.is_none() => {
                     let name = format!("{}::{}", path.join("::"), s.name(db).display(db));
 
                     for assoc_item in impl_def.items(db) {
-                        if let AssocItem::Function(f) = assoc_item {
-                            self.methods.insert(f, name.clone());
+                        match assoc_item {
+                            AssocItem::Function(f) => {
+                                add_in(&mut self.methods, db, f, &name);
+                            }
+                            AssocItem::Const(c) => {
+                                add_in(&mut self.consts, db, c, &name);
+                            }
+                            _ => {}
                         }
                     }
                 }
-                // Load enum instance methods
+                // Load enum instance methods && associated constants
                 Some(Adt::Enum(e)) if target_trait.is_none() => {
                     let name = format!("{}::{}", path.join("::"), e.name(db).display(db));
 
                     for assoc_item in impl_def.items(db) {
-                        if let AssocItem::Function(f) = assoc_item {
-                            self.methods.insert(f, name.clone());
+                        match assoc_item {
+                            AssocItem::Function(f) => {
+                                add_in(&mut self.methods, db, f, &name);
+                            }
+                            AssocItem::Const(c) => {
+                                add_in(&mut self.consts, db, c, &name);
+                            }
+                            _ => {}
                         }
                     }
                 }
-                // Load union instance methods
+                // Load union instance methods && associated constants
                 Some(Adt::Union(u)) if target_trait.is_none() => {
                     let name = format!("{}::{}", path.join("::"), u.name(db).display(db));
 
                     for assoc_item in impl_def.items(db) {
-                        if let AssocItem::Function(f) = assoc_item {
-                            self.methods.insert(f, name.clone());
+                        match assoc_item {
+                            AssocItem::Function(f) => {
+                                add_in(&mut self.methods, db, f, &name);
+                            }
+                            AssocItem::Const(c) => {
+                                add_in(&mut self.consts, db, c, &name);
+                            }
+                            _ => {}
                         }
                     }
                 }
@@ -113,4 +679,109 @@ impl Preloader {
             }
         }
     }
+
+    /// Returns the loaded crate the path is in when nothing can be reached at it
+    pub(crate) fn unmatched<'a>(&self, path: &'a str) -> Option<&'a str> {
+        let krate = path.split("::").next()?;
+
+        if self.visited.iter().any(|x| x == krate) && !self.paths.contains(path) {
+            Some(krate)
+        } else {
+            None
+        }
+    }
+
+    /// Adds the paths of the items publicly re-exported in the module, recursing into public modules
+    fn load_reexports(
+        &mut self,
+        db: &RootDatabase,
+        module: &Module,
+        path: Vec<String>,
+        outside: Option<Module>,
+        visited: &mut Vec<Module>,
+        aliases: &mut Vec<(String, String)>,
+    ) {
+        if visited.contains(module) {
+            return;
+        }
+
+        visited.push(*module);
+
+        let parent = path.join("::");
+        self.paths.insert(parent.clone());
+
+        for (name, def) in module.scope(db, outside) {
+            let name = format!("{}", name.display(db));
+            let full = format!("{}::{}", parent, name);
+
+            let def = match def {
+                ScopeDef::ModuleDef(x) => x,
+                _ => continue,
+            };
+
+            // Types and traits are aliased by their original full path
+            let original = |paths: Option<&Vec<String>>| {
+                paths
+                    .and_then(|x| x.first())
+                    .map(|x| (x.clone(), full.clone()))
+            };
+
+            let alias = match def {
+                ModuleDef::Module(m) => {
+                    if m.krate() == module.krate() {
+                        let mut path = path.clone();
+                        path.push(name);
+
+                        self.load_reexports(db, &m, path, outside, visited, aliases);
+                    }
+
+                    continue;
+                }
+                ModuleDef::Function(f) => {
+                    add(&mut self.functions, f, full.clone());
+                    None
+                }
+                ModuleDef::Adt(Adt::Struct(s)) => {
+                    let alias = original(self.structs.get(&s));
+                    add(&mut self.structs, s, full.clone());
+                    alias
+                }
+                ModuleDef::Adt(Adt::Union(u)) => {
+                    let alias = original(self.unions.get(&u));
+                    add(&mut self.unions, u, full.clone());
+                    alias
+                }
+                ModuleDef::Adt(Adt::Enum(e)) => {
+                    let alias = original(self.enums.get(&e));
+                    add(&mut self.enums, e, full.clone());
+                    alias
+                }
+                ModuleDef::Trait(t) => {
+                    let alias = original(self.traits.get(&t));
+                    add(&mut self.traits, t, full.clone());
+                    alias
+                }
+                ModuleDef::Const(c) => {
+                    add(&mut self.consts, c, full.clone());
+                    None
+                }
+                ModuleDef::Static(s) => {
+                    add(&mut self.statics, s, full.clone());
+                    None
+                }
+                ModuleDef::Macro(m) => {
+                    add(&mut self.macros, m, full.clone());
+                    None
+                }
+                ModuleDef::TypeAlias(t) => {
+                    add(&mut self.type_aliases, t, full.clone());
+                    None
+                }
+                _ => continue,
+            };
+
+            self.paths.insert(full);
+            aliases.extend(alias);
+        }
+    }
 }
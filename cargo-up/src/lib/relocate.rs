@@ -0,0 +1,655 @@
+use crate::{
+    ra_ap_syntax::{
+        ast::{self, AstNode, HasAttrs, HasVisibility},
+        NodeOrToken, SyntaxKind, SyntaxNode, TextRange, TextSize,
+    },
+    utils::normalize,
+    Semantics, Upgrader,
+};
+
+use ra_ap_hir::{Crate, ModuleDef, PathResolution};
+
+use std::iter::successors;
+
+/// Pairs of old and new paths. A path is relocated using the longest old path
+/// which is a prefix of it.
+pub(crate) type PathMap = Vec<(String, String)>;
+
+type Segments = Vec<String>;
+
+pub(crate) fn split(path: &str) -> Segments {
+    path.split("::").map(|x| x.to_string()).collect()
+}
+
+pub(crate) fn segments(path: &ast::Path) -> Option<Segments> {
+    path.segments()
+        .map(|x| Some(x.name_ref()?.text().to_string()))
+        .collect()
+}
+
+/// Returns the number of leading segments matched and what they should be replaced with
+fn relocate(map: &PathMap, segments: &[String]) -> Option<(usize, Segments)> {
+    map.iter()
+        .map(|(from, to)| (split(from), to))
+        .filter(|(from, _)| from.len() <= segments.len() && from[..] == segments[..from.len()])
+        .max_by_key(|(from, _)| from.len())
+        .map(|(from, to)| (from.len(), split(to)))
+}
+
+fn relocate_all(map: &PathMap, segments: &[String]) -> Segments {
+    match relocate(map, segments) {
+        Some((len, mut to)) => {
+            to.extend_from_slice(&segments[len..]);
+            to
+        }
+        None => segments.to_vec(),
+    }
+}
+
+/// Edit replacing the first `len` segments of the path, keeping the leading `::`
+/// and the generic args of the last replaced segment.
+fn path_edit(path: &ast::Path, len: usize, to: &[String]) -> Option<(TextRange, String)> {
+    let total = path.segments().count();
+    let matched = successors(Some(path.clone()), |x| x.qualifier()).nth(total.checked_sub(len)?)?;
+
+    let start = matched.syntax().text_range().start();
+    let end = matched.segment()?.name_ref()?.syntax().text_range().end();
+
+    let prefix = if path.first_segment()?.coloncolon_token().is_some() {
+        "::"
+    } else {
+        ""
+    };
+
+    Some((
+        TextRange::new(start, end),
+        format!("{}{}", prefix, to.join("::")),
+    ))
+}
+
+/// Whether the first segment of the path is the root of the crate `name`
+pub(crate) fn is_crate_path(semantics: &Semantics, path: &ast::Path, name: &str) -> bool {
+    match semantics.resolve_path(&path.first_qualifier_or_self()) {
+        Some(PathResolution::Def(ModuleDef::Module(module))) => {
+            module.is_crate_root()
+                && module
+                    .krate()
+                    .display_name(semantics.db)
+                    .is_some_and(|x| normalize(&x.to_string()) == normalize(name))
+        }
+        _ => false,
+    }
+}
+
+/// Name of the crate as given in `crates`, when it is one of them
+fn crate_name(semantics: &Semantics, krate: Crate, crates: &[String]) -> Option<String> {
+    let name = normalize(&krate.display_name(semantics.db)?.to_string());
+    crates.iter().find(|x| normalize(x) == name).cloned()
+}
+
+/// Full path of the module the path resolves to, when it belongs to one of the crates
+fn module_path(semantics: &Semantics, path: &ast::Path, crates: &[String]) -> Option<Segments> {
+    let module = match semantics.resolve_path(path)? {
+        PathResolution::Def(ModuleDef::Module(module)) => module,
+        _ => return None,
+    };
+
+    let mut segments = vec![crate_name(semantics, module.krate(), crates)?];
+    segments.extend(
+        module
+            .path_to_root(semantics.db)
+            .into_iter()
+            .rev()
+            .filter_map(|x| Some(x.name(semantics.db)?.display(semantics.db).to_string())),
+    );
+
+    Some(segments)
+}
+
+/// Plans the relocation of a path used outside of imports. The path is read from its first
+/// qualifier resolving to a module of the crates, so that the paths going through an
+/// imported module or its alias are relocated too. Returns the number of leading segments
+/// to replace, what to replace them with and whether that qualifier is replaced as well.
+fn plan_path(
+    semantics: &Semantics,
+    path: &ast::Path,
+    map: &PathMap,
+    crates: &[String],
+) -> Option<(usize, Segments, bool)> {
+    let qualifiers = successors(Some(path.clone()), |x| x.qualifier()).collect::<Vec<_>>();
+    let (qualifier, base) = qualifiers
+        .iter()
+        .rev()
+        .find_map(|x| Some((segments(x)?, module_path(semantics, x, crates)?)))?;
+
+    let full = [&base[..], &segments(path)?[qualifier.len()..]].concat();
+    let (len, to) = relocate(map, &full)?;
+
+    // An imported module which is relocated is taken care of along with its import
+    if len <= base.len() && qualifier != base {
+        return None;
+    }
+
+    let len = qualifier.len() + len - base.len();
+
+    if to.len() >= base.len() && to[..base.len()] == base[..] {
+        Some((len, [&qualifier[..], &to[base.len()..]].concat(), false))
+    } else {
+        Some((len, to, true))
+    }
+}
+
+/// Relocates a path used outside of imports. Only the outermost path should be given.
+pub(crate) fn relocate_path(
+    upgrader: &mut Upgrader,
+    semantics: &Semantics,
+    path: &ast::Path,
+    map: &PathMap,
+    crates: &[String],
+) -> Option<()> {
+    let (len, to, _) = match plan_path(semantics, path, map, crates) {
+        Some(plan) => plan,
+        None if path.qualifier().is_none() => {
+            return warn_unimported(upgrader, semantics, path, map, crates);
+        }
+        None => return None,
+    };
+
+    if to[..] != segments(path)?[..len] {
+        match path_edit(path, len, &to) {
+            Some((range, text)) => upgrader.replace(range, text),
+            None => upgrader.warn(
+                path.syntax().text_range(),
+                format!("Unable to relocate `{}` to `{}`", path, to.join("::")),
+            ),
+        }
+    }
+
+    Some(())
+}
+
+/// Warns about a name referring to a relocated item which is not imported by its path in
+/// the module, e.g. through a glob import, since it can not be rewritten
+fn warn_unimported(
+    upgrader: &mut Upgrader,
+    semantics: &Semantics,
+    path: &ast::Path,
+    map: &PathMap,
+    crates: &[String],
+) -> Option<()> {
+    let def = match semantics.resolve_path(path)? {
+        PathResolution::Def(ModuleDef::Variant(_)) => return None,
+        PathResolution::Def(def) => def,
+        _ => return None,
+    };
+
+    let krate = crate_name(semantics, def.module(semantics.db)?.krate(), crates)?;
+    let full = [vec![krate], split(&def.canonical_path(semantics.db)?)].concat();
+    let to = relocate_all(map, &full);
+
+    if to == full {
+        return None;
+    }
+
+    let name = path.segment()?.name_ref()?.text().to_string();
+    let imported = path
+        .syntax()
+        .ancestors()
+        .take_while(|x| !ast::Module::can_cast(x.kind()))
+        .flat_map(|x| x.children())
+        .filter_map(ast::Use::cast)
+        .filter_map(|x| use_leaves(&x.use_tree()?))
+        .flatten()
+        .any(|x| !x.glob && x.name() == Some(name.as_str()));
+
+    if !imported {
+        upgrader.warn(
+            path.syntax().text_range(),
+            format!(
+                "Unable to relocate `{}` to `{}`, it is not imported by its path",
+                name,
+                to.join("::")
+            ),
+        );
+    }
+
+    Some(())
+}
+
+/// Relocates all the paths of a top level use tree. Nested groups are kept as is
+/// when possible, otherwise the tree is flattened and regrouped under the common prefix. Items
+/// whose name changed are aliased to their old name so that the code using them
+/// keeps working.
+pub(crate) fn relocate_use_tree(
+    upgrader: &mut Upgrader,
+    tree: &ast::UseTree,
+    map: &PathMap,
+) -> Option<()> {
+    let mut edits = vec![];
+
+    if plan_use_tree(tree, &[], &[], map, &mut edits).is_some() {
+        for (range, text) in edits {
+            upgrader.replace(range, text);
+        }
+
+        return Some(());
+    }
+
+    let leaves = use_leaves(tree)?
+        .iter()
+        .map(|x| x.relocate(map))
+        .collect::<Vec<_>>();
+
+    upgrader.replace(tree.syntax().text_range(), render_use_tree(&leaves));
+    Some(())
+}
+
+fn plan_use_tree(
+    tree: &ast::UseTree,
+    old_parent: &[String],
+    new_parent: &[String],
+    map: &PathMap,
+    edits: &mut Vec<(TextRange, String)>,
+) -> Option<()> {
+    let is_leaf = tree.use_tree_list().is_none() && tree.star_token().is_none();
+
+    let (old, new) = match tree.path() {
+        Some(path) if segments(&path)? == ["self"] => {
+            if relocate_all(map, old_parent) != new_parent {
+                return None;
+            }
+
+            if tree.rename().is_none()
+                && old_parent.len() > 1
+                && old_parent.last() != new_parent.last()
+            {
+                edits.push((
+                    path.syntax().text_range(),
+                    format!("self as {}", old_parent.last()?),
+                ));
+            }
+
+            (old_parent.to_vec(), new_parent.to_vec())
+        }
+        Some(path) => {
+            let segments = segments(&path)?;
+            let old = [old_parent, &segments].concat();
+            let new = relocate_all(map, &old);
+
+            if new.len() <= new_parent.len() || new[..new_parent.len()] != new_parent[..] {
+                return None;
+            }
+
+            let relative = &new[new_parent.len()..];
+            let renamed =
+                is_leaf && tree.rename().is_none() && old.len() > 1 && old.last() != new.last();
+
+            if relative != &segments[..] || renamed {
+                let (range, mut text) = path_edit(&path, segments.len(), relative)?;
+
+                if renamed {
+                    text.push_str(&format!(" as {}", old.last()?));
+                }
+
+                edits.push((range, text));
+            }
+
+            (old, new)
+        }
+        None => (old_parent.to_vec(), new_parent.to_vec()),
+    };
+
+    if let Some(list) = tree.use_tree_list() {
+        for child in list.use_trees() {
+            plan_use_tree(&child, &old, &new, map, edits)?;
+        }
+    }
+
+    Some(())
+}
+
+/// A single path imported by a use tree, with the nested groups resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UseLeaf {
+    pub(crate) path: Segments,
+    pub(crate) rename: Option<String>,
+    pub(crate) glob: bool,
+}
+
+impl UseLeaf {
+    fn relocate(&self, map: &PathMap) -> Self {
+        let path = relocate_all(map, &self.path);
+
+        let rename = match &self.rename {
+            Some(rename) => Some(rename.clone()),
+            None if !self.glob && self.path.len() > 1 && self.path.last() != path.last() => {
+                self.path.last().map(|x| format!("as {}", x))
+            }
+            None => None,
+        };
+
+        Self {
+            path,
+            rename,
+            glob: self.glob,
+        }
+    }
+
+    /// Name brought into scope by the leaf
+    fn name(&self) -> Option<&str> {
+        match &self.rename {
+            Some(rename) => rename.strip_prefix("as ").map(str::trim),
+            None => self.path.last().map(|x| x.as_str()),
+        }
+    }
+
+    /// Renders the leaf relative to the first `skip` segments of its path
+    pub(crate) fn render(&self, skip: usize) -> String {
+        let mut text = self.path[skip..].join("::");
+
+        if self.glob {
+            text.push_str(if text.is_empty() { "*" } else { "::*" });
+        } else {
+            if text.is_empty() {
+                text.push_str("self");
+            }
+
+            if let Some(rename) = &self.rename {
+                text.push_str(&format!(" {}", rename));
+            }
+        }
+
+        text
+    }
+}
+
+/// Collects the leaves of a top level use tree
+pub(crate) fn use_leaves(tree: &ast::UseTree) -> Option<Vec<UseLeaf>> {
+    let mut leaves = vec![];
+    collect_leaves(tree, &[], &mut leaves)?;
+    Some(leaves)
+}
+
+fn collect_leaves(tree: &ast::UseTree, parent: &[String], leaves: &mut Vec<UseLeaf>) -> Option<()> {
+    let path = match tree.path() {
+        Some(path) if segments(&path)? == ["self"] => parent.to_vec(),
+        Some(path) => [parent, &segments(&path)?].concat(),
+        None => parent.to_vec(),
+    };
+
+    if let Some(list) = tree.use_tree_list() {
+        for child in list.use_trees() {
+            collect_leaves(&child, &path, leaves)?;
+        }
+
+        return Some(());
+    }
+
+    leaves.push(UseLeaf {
+        path,
+        rename: tree.rename().map(|x| x.to_string()),
+        glob: tree.star_token().is_some(),
+    });
+
+    Some(())
+}
+
+/// Renders the leaves as a single use tree, grouping them by their common prefixes
+pub(crate) fn render_use_tree(leaves: &[UseLeaf]) -> String {
+    let mut items = render_group(leaves, 0);
+
+    if items.len() == 1 {
+        items.remove(0)
+    } else {
+        format!("{{{}}}", items.join(", "))
+    }
+}
+
+fn render_group(leaves: &[UseLeaf], depth: usize) -> Vec<String> {
+    let mut groups: Vec<(Option<&String>, Vec<UseLeaf>)> = vec![];
+
+    for leaf in leaves {
+        let key = leaf.path.get(depth);
+
+        match groups.iter_mut().find(|x| key.is_some() && x.0 == key) {
+            Some((_, group)) => group.push(leaf.clone()),
+            None => groups.push((key, vec![leaf.clone()])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(key, group)| match key {
+            Some(key) if group.len() > 1 => {
+                let items = render_group(&group, depth + 1);
+
+                if items.len() == 1 {
+                    format!("{}::{}", key, items[0])
+                } else {
+                    format!("{}::{{{}}}", key, items.join(", "))
+                }
+            }
+            _ => group[0].render(depth),
+        })
+        .collect()
+}
+
+/// Whether the use tree imports from one of the given crates
+fn imports_crate(semantics: &Semantics, tree: &ast::UseTree, crates: &[String]) -> bool {
+    match tree.path() {
+        Some(path) => crates.iter().any(|x| is_crate_path(semantics, &path, x)),
+        None => true,
+    }
+}
+
+/// Whether both imports can share a use tree
+fn is_mergeable(a: &ast::Use, b: &ast::Use) -> bool {
+    a.attrs().next().is_none()
+        && b.attrs().next().is_none()
+        && a.visibility().map(|x| x.to_string()) == b.visibility().map(|x| x.to_string())
+}
+
+/// Range of the item along with the line break following it
+fn item_range(node: &SyntaxNode) -> TextRange {
+    let range = node.text_range();
+
+    match node.next_sibling_or_token() {
+        Some(NodeOrToken::Token(token))
+            if token.kind() == SyntaxKind::WHITESPACE && token.text().starts_with('\n') =>
+        {
+            TextRange::new(
+                range.start(),
+                token.text_range().start() + TextSize::of('\n'),
+            )
+        }
+        _ => range,
+    }
+}
+
+/// Whether the module imported by the leaf is only used by paths which are relocated out of
+/// it, so that the import is left unused after the upgrade
+fn is_left_unused(
+    semantics: &Semantics,
+    item: &ast::Use,
+    leaf: &UseLeaf,
+    map: &PathMap,
+    crates: &[String],
+) -> bool {
+    let name = match leaf.name() {
+        Some(name) if !leaf.glob && name != "_" => name,
+        _ => return false,
+    };
+
+    let has_moved = map.iter().any(|(from, _)| {
+        let from = split(from);
+        from.len() > leaf.path.len() && from[..leaf.path.len()] == leaf.path[..]
+    });
+
+    let scope = match item.syntax().parent() {
+        Some(scope) if has_moved => scope,
+        _ => return false,
+    };
+
+    let mut used = false;
+
+    for token in scope
+        .descendants_with_tokens()
+        .filter_map(|x| x.into_token())
+    {
+        if token.kind() != SyntaxKind::IDENT
+            || token.text() != name
+            || item
+                .syntax()
+                .text_range()
+                .contains_range(token.text_range())
+        {
+            continue;
+        }
+
+        // Any other use of the name, e.g. inside a macro call or another import, keeps it
+        let path = token
+            .parent()
+            .and_then(ast::NameRef::cast)
+            .and_then(|x| x.syntax().parent())
+            .and_then(ast::PathSegment::cast)
+            .map(|x| x.parent_path())
+            .filter(|x| x.qualifier().is_none())
+            .filter(|x| !x.syntax().ancestors().any(|x| ast::Use::can_cast(x.kind())));
+
+        let relocated = path
+            .and_then(|x| successors(Some(x), |x| x.parent_path()).last())
+            .and_then(|x| plan_path(semantics, &x, map, crates))
+            .is_some_and(|(_, _, replaced)| replaced);
+
+        if !relocated {
+            return false;
+        }
+
+        used = true;
+    }
+
+    used
+}
+
+/// Relocates the paths of an import while taking the other imports of the same scope
+/// into account. Relocated paths which are already imported are dropped and the ones
+/// that fit an existing group are merged into it. Modules which are only used by paths
+/// relocated out of them are dropped as well. The import is removed when nothing is left
+/// of it.
+pub(crate) fn move_use(
+    upgrader: &mut Upgrader,
+    semantics: &Semantics,
+    item: &ast::Use,
+    map: &PathMap,
+    crates: &[String],
+) -> Option<()> {
+    let tree = item.use_tree()?;
+
+    if !imports_crate(semantics, &tree, crates) {
+        return None;
+    }
+
+    let leaves = use_leaves(&tree)?;
+    let moved = leaves.iter().map(|x| x.relocate(map)).collect::<Vec<_>>();
+    let unused = leaves
+        .iter()
+        .filter(|x| is_left_unused(semantics, item, x, map, crates))
+        .collect::<Vec<_>>();
+
+    if moved == leaves && unused.is_empty() {
+        return None;
+    }
+
+    // Imports of the scope as they will be after the upgrade, and the groups which
+    // are left untouched by it
+    let mut existing = vec![];
+    let mut groups = vec![];
+    let mut before = true;
+
+    for sibling in item
+        .syntax()
+        .parent()?
+        .children()
+        .filter_map(ast::Use::cast)
+    {
+        if sibling == *item {
+            before = false;
+            continue;
+        }
+
+        if !is_mergeable(item, &sibling) {
+            continue;
+        }
+
+        let sibling_tree = match sibling.use_tree() {
+            Some(x) => x,
+            None => continue,
+        };
+
+        let sibling_leaves = match use_leaves(&sibling_tree) {
+            Some(x) => x,
+            None => continue,
+        };
+
+        let sibling_moved = if imports_crate(semantics, &sibling_tree, crates) {
+            sibling_leaves.iter().map(|x| x.relocate(map)).collect()
+        } else {
+            sibling_leaves.clone()
+        };
+
+        if sibling_moved == sibling_leaves {
+            if let (Some(path), Some(list)) = (sibling_tree.path(), sibling_tree.use_tree_list()) {
+                if let (Some(path), Some(last)) = (segments(&path), list.use_trees().last()) {
+                    groups.push((path, last));
+                }
+            }
+
+            existing.extend(sibling_leaves);
+        } else if before {
+            existing.extend(sibling_moved);
+        } else {
+            existing.extend(
+                sibling_leaves
+                    .into_iter()
+                    .zip(sibling_moved)
+                    .filter(|(old, new)| old == new)
+                    .map(|(old, _)| old),
+            );
+        }
+    }
+
+    let mut keep: Vec<UseLeaf> = vec![];
+
+    for (old, new) in leaves.iter().zip(&moved) {
+        if unused.contains(&old) || existing.contains(new) || keep.contains(new) {
+            continue;
+        }
+
+        if old != new {
+            let group = groups.iter().find(|(path, _)| {
+                new.path.len() > path.len() && new.path[..path.len()] == path[..]
+            });
+
+            if let Some((path, last)) = group {
+                upgrader.insert(
+                    last.syntax().text_range().end(),
+                    format!(", {}", new.render(path.len())),
+                );
+                existing.push(new.clone());
+                continue;
+            }
+        }
+
+        keep.push(new.clone());
+    }
+
+    if keep.is_empty() {
+        upgrader.delete(item_range(item.syntax()));
+    } else if keep == moved {
+        relocate_use_tree(upgrader, &tree, map)?;
+    } else {
+        upgrader.replace(tree.syntax().text_range(), render_use_tree(&keep));
+    }
+
+    Some(())
+}
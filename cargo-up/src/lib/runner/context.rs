@@ -1,24 +1,44 @@
 use crate::{
-    preloader::Preloader, semver::Version as SemverVersion, utils::INTERNAL_ERR, Runner, Semantics,
-    Upgrader,
+    preloader::Preloader,
+    ra_ap_syntax::{ast, AstNode, SyntaxNode, TextRange},
+    runner::expansion::original_range,
+    semver::Version as SemverVersion,
+    upgrader::{Level, Note, Origin},
+    utils::INTERNAL_ERR,
+    Runner, Semantics, Upgrader,
 };
 
 use anyhow::Result as AnyResult;
+use ra_ap_base_db::FileRange;
+use ra_ap_text_edit::Indel;
+use rust_visitor::Visitor;
+
+use std::mem;
 
 pub(crate) struct Context<'a> {
     pub(crate) runner: Runner,
     pub(crate) preloader: Preloader,
     pub(crate) upgrader: Upgrader,
     pub(crate) semantics: Semantics<'a>,
+    call_site: Option<FileRange>,
+    expansion: Option<SyntaxNode>,
+    expanded: Vec<(Indel, Origin)>,
+    expanded_notes: Vec<Note>,
+    expanded_imports: Vec<(TextRange, String)>,
 }
 
 impl<'a> Context<'a> {
-    pub(crate) fn new(runner: Runner, semantics: Semantics<'a>) -> Self {
+    pub(crate) fn new(runner: Runner, preloader: Preloader, semantics: Semantics<'a>) -> Self {
         Self {
             runner,
-            preloader: Preloader::default(),
+            preloader,
             upgrader: Upgrader::default(),
             semantics,
+            call_site: None,
+            expansion: None,
+            expanded: vec![],
+            expanded_notes: vec![],
+            expanded_imports: vec![],
         }
     }
 
@@ -31,4 +51,90 @@ impl<'a> Context<'a> {
             Ok(())
         }
     }
+
+    /// Returns whether the node was written at the call site of the macro being expanded,
+    /// and not generated by the macro
+    pub(crate) fn is_written(&self, node: &SyntaxNode) -> bool {
+        match (&self.expansion, self.call_site) {
+            (Some(expansion), Some(call_site)) => {
+                original_range(&self.semantics, expansion, call_site, node.text_range()).is_some()
+            }
+            _ => true,
+        }
+    }
+
+    /// Walks the expansion of the macro call and applies the changes made inside it
+    /// to the tokens written at the outermost call site.
+    pub(crate) fn walk_expansion(&mut self, macro_call: &ast::MacroCall) {
+        let expansion = match self.semantics.expand(macro_call) {
+            Some(x) => x,
+            None => return,
+        };
+
+        let is_outermost = self.call_site.is_none();
+
+        if is_outermost {
+            self.call_site = self.semantics.original_range_opt(macro_call.syntax());
+        }
+
+        let call_site = match self.call_site {
+            Some(x) => x,
+            None => return,
+        };
+
+        let outer = mem::take(&mut self.upgrader);
+        let outer_expansion = self.expansion.replace(expansion.clone());
+        self.walk(&expansion);
+        self.expansion = outer_expansion;
+        let mut inner = mem::replace(&mut self.upgrader, outer);
+
+        self.upgrader.extend_manifest(inner.finish_manifest());
+
+        for (indel, origin) in inner.finish_edits() {
+            match original_range(&self.semantics, &expansion, call_site, indel.delete) {
+                Some(range) => {
+                    let edit = (Indel::replace(range, indel.insert), origin);
+
+                    // Macros can use the same argument more than once
+                    if !self.expanded.contains(&edit) {
+                        self.expanded.push(edit);
+                    }
+                }
+                None => self.expanded_notes.push(Note {
+                    level: Level::Warning,
+                    range: call_site.range,
+                    message: "Unable to apply the changes inside the macro call".to_string(),
+                }),
+            }
+        }
+
+        for mut note in inner.finish_notes() {
+            note.range = original_range(&self.semantics, &expansion, call_site, note.range)
+                .unwrap_or(call_site.range);
+
+            self.expanded_notes.push(note);
+        }
+
+        for (range, path) in inner.finish_imports() {
+            let range = original_range(&self.semantics, &expansion, call_site, range)
+                .unwrap_or(call_site.range);
+
+            self.expanded_imports.push((range, path));
+        }
+
+        if is_outermost {
+            self.upgrader
+                .extend_edits(self.expanded.drain(..).collect());
+
+            for note in self.expanded_notes.drain(..) {
+                self.upgrader.note(note.level, note.range, note.message);
+            }
+
+            for (range, path) in self.expanded_imports.drain(..) {
+                self.upgrader.ensure_import(range, &path);
+            }
+
+            self.call_site = None;
+        }
+    }
 }
@@ -0,0 +1,21 @@
+use similar::TextDiff;
+
+/// Renders the change to a single file as a `git apply`-able unified diff.
+pub(crate) fn unified_diff(path: &str, before: &str, after: &str) -> String {
+    if before == after {
+        return String::new();
+    }
+
+    let old = format!("a/{}", path);
+    let new = format!("b/{}", path);
+
+    format!(
+        "diff --git {} {}\n{}",
+        old,
+        new,
+        TextDiff::from_lines(before, after)
+            .unified_diff()
+            .context_radius(3)
+            .header(&old, &new),
+    )
+}
@@ -0,0 +1,97 @@
+use crate::{
+    ra_ap_syntax::{NodeOrToken, SyntaxNode, SyntaxToken, TextRange},
+    Semantics,
+};
+
+use ra_ap_base_db::FileRange;
+use ra_ap_hir::InFile;
+
+/// Maps a range of a macro expansion back to the file being upgraded. Only ranges made
+/// of whole tokens which were all written, in order and next to each other, inside the
+/// `call_site` can be mapped.
+pub(crate) fn original_range(
+    semantics: &Semantics,
+    expansion: &SyntaxNode,
+    call_site: FileRange,
+    range: TextRange,
+) -> Option<TextRange> {
+    let file_id = semantics.hir_file_for(expansion);
+
+    let map = |token: SyntaxToken| {
+        let original = InFile::new(file_id, token).original_file_range_opt(semantics.db)?;
+
+        if original.file_id == call_site.file_id && call_site.range.contains_range(original.range) {
+            Some(original.range)
+        } else {
+            None
+        }
+    };
+
+    if range.is_empty() {
+        let start = expansion
+            .token_at_offset(range.start())
+            .right_biased()
+            .filter(|x| x.text_range().start() == range.start());
+        let end = expansion
+            .token_at_offset(range.end())
+            .left_biased()
+            .filter(|x| x.text_range().end() == range.end());
+
+        // Insertions next to a token of the macro itself stick to the other token
+        return start
+            .and_then(map)
+            .map(|x| x.start())
+            .or_else(|| end.and_then(map).map(|x| x.end()))
+            .map(TextRange::empty);
+    }
+
+    let tokens = tokens_in(expansion, range);
+
+    match (tokens.first(), tokens.last()) {
+        (Some(first), Some(last))
+            if first.text_range().start() == range.start()
+                && last.text_range().end() == range.end() => {}
+        _ => return None,
+    }
+
+    let mut mapped = Vec::with_capacity(tokens.len());
+
+    for token in tokens {
+        let original = map(token)?;
+
+        // Every token has to come after the previous one, which rules out
+        // arguments used more than once and tokens coming from the macro itself
+        if let Some(prev) = mapped.last().map(|x: &TextRange| x.end()) {
+            if original.start() < prev {
+                return None;
+            }
+        }
+
+        mapped.push(original);
+    }
+
+    let original = TextRange::new(mapped.first()?.start(), mapped.last()?.end());
+    let source = semantics.parse(call_site.file_id);
+
+    // No other token of the call site can be left between the mapped ones
+    if tokens_in(source.syntax(), original).len() == mapped.len() {
+        Some(original)
+    } else {
+        None
+    }
+}
+
+fn tokens_in(node: &SyntaxNode, range: TextRange) -> Vec<SyntaxToken> {
+    let tokens = match node.covering_element(range) {
+        NodeOrToken::Token(token) => vec![token],
+        NodeOrToken::Node(node) => node
+            .descendants_with_tokens()
+            .filter_map(|x| x.into_token())
+            .collect(),
+    };
+
+    tokens
+        .into_iter()
+        .filter(|x| !x.kind().is_trivia() && range.contains_range(x.text_range()))
+        .collect()
+}
@@ -0,0 +1,145 @@
+use crate::ra_ap_syntax::{
+    ast::{HasModuleItem, SourceFile},
+    AstNode, TextRange, TextSize,
+};
+
+use log::debug;
+use ra_ap_text_edit::{TextEdit, TextEditBuilder};
+use similar::{DiffTag, TextDiff};
+
+use std::{
+    env::var_os,
+    ffi::OsString,
+    io::Write,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+/// Which parts of the changed files are formatted with rustfmt
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Only the top level items containing changes
+    Items,
+    /// The whole files containing changes
+    Files,
+}
+
+/// Formats the changed parts of the upgraded text with the rustfmt config found from `dir`,
+/// returning the edit to apply to it
+pub(crate) fn format_changes(
+    dir: &Path,
+    format: Format,
+    edition: &str,
+    original: &str,
+    text: &str,
+) -> TextEdit {
+    let formatted = match format {
+        Format::Items => format_items(dir, edition, original, text),
+        Format::Files => rustfmt(dir, edition, text),
+    };
+
+    match formatted {
+        Some(formatted) => line_edit(text, &formatted),
+        None => TextEdit::default(),
+    }
+}
+
+fn format_items(dir: &Path, edition: &str, original: &str, text: &str) -> Option<String> {
+    let offsets = line_offsets(text);
+    let changed = TextDiff::from_lines(original, text)
+        .ops()
+        .iter()
+        .filter(|x| x.tag() != DiffTag::Equal)
+        .map(|x| {
+            let lines = x.new_range();
+            TextRange::new(offsets[lines.start], offsets[lines.end])
+        })
+        .collect::<Vec<_>>();
+
+    let mut builder = TextEditBuilder::default();
+
+    for item in SourceFile::parse(text).tree().items() {
+        let range = item.syntax().text_range();
+
+        if !changed.iter().any(|x| x.intersect(range).is_some()) {
+            continue;
+        }
+
+        if let Some(formatted) = rustfmt(dir, edition, &item.syntax().to_string()) {
+            builder.replace(range, formatted.trim_end().to_string());
+        }
+    }
+
+    let mut text = text.to_string();
+    builder.finish().apply(&mut text);
+    Some(text)
+}
+
+/// Runs rustfmt on the text, returning nothing if it can not be formatted
+fn rustfmt(dir: &Path, edition: &str, text: &str) -> Option<String> {
+    let rustfmt = var_os("RUSTFMT").unwrap_or_else(|| OsString::from("rustfmt"));
+
+    // rustfmt looks for the config starting from the current dir when reading stdin
+    let mut child = Command::new(rustfmt)
+        .args(["--edition", edition])
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+
+    child.stdin.take()?.write_all(text.as_bytes()).ok()?;
+
+    let output = child.wait_with_output().ok()?;
+
+    if !output.status.success() {
+        debug!(
+            "Unable to format: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        return None;
+    }
+
+    String::from_utf8(output.stdout).ok()
+}
+
+/// Returns the edit replacing the lines which differ between the texts
+fn line_edit(before: &str, after: &str) -> TextEdit {
+    let old_offsets = line_offsets(before);
+    let new_offsets = line_offsets(after);
+    let mut builder = TextEditBuilder::default();
+
+    for op in TextDiff::from_lines(before, after).ops() {
+        if op.tag() == DiffTag::Equal {
+            continue;
+        }
+
+        let (old, new) = (op.old_range(), op.new_range());
+        let (start, end): (usize, usize) =
+            (new_offsets[new.start].into(), new_offsets[new.end].into());
+
+        builder.replace(
+            TextRange::new(old_offsets[old.start], old_offsets[old.end]),
+            after[start..end].to_string(),
+        );
+    }
+
+    builder.finish()
+}
+
+/// Offsets of the start of each line along with the end of the text
+fn line_offsets(text: &str) -> Vec<TextSize> {
+    let mut offsets = vec![TextSize::from(0)];
+    offsets.extend(
+        text.match_indices('\n')
+            .map(|(x, _)| TextSize::from(x as u32 + 1)),
+    );
+
+    if offsets.last() != Some(&TextSize::of(text)) {
+        offsets.push(TextSize::of(text));
+    }
+
+    offsets
+}
@@ -1,10 +1,17 @@
 use crate::{
-    ra_ap_syntax::ast::{Name, NameOrNameRef, NameRef, Path},
+    preloader::{Named, Table},
+    ra_ap_syntax::{
+        ast::{Adt, Attr, CallExpr, Expr, HasAttrs, Name, NameOrNameRef, NameRef, Path},
+        AstNode,
+    },
     version::Hooks,
     Semantics, Upgrader,
 };
 
-use std::{collections::HashMap as Map, fmt::Debug};
+use ra_ap_hir::{AsAssocItem, AssocItem, Function, Macro, ModuleDef, PathResolution, Trait, Type};
+use ra_ap_ide_db::RootDatabase;
+
+use std::fmt::Debug;
 
 pub(crate) fn get_name(name_or_name_ref: Option<NameOrNameRef>) -> Option<String> {
     Some(match name_or_name_ref? {
@@ -25,17 +32,131 @@ pub(crate) fn get_name_from_path(path: Option<Path>) -> Option<String> {
     get_name_from_name_ref(path?.segment()?.name_ref())
 }
 
+/// Returns the path of the called item if the callee is a path
+pub(crate) fn get_callee_path(call_expr: &CallExpr) -> Option<Path> {
+    match call_expr.expr()? {
+        Expr::PathExpr(path_expr) => path_expr.path(),
+        _ => None,
+    }
+}
+
+/// Returns the trait declaration of a function if it is a trait method or implements one
+pub(crate) fn get_trait_function(db: &RootDatabase, function: Function) -> Option<Function> {
+    let assoc_item = function.as_assoc_item(db)?;
+
+    if assoc_item.container_trait(db).is_some() {
+        return Some(function);
+    }
+
+    let name = function.name(db);
+
+    assoc_item
+        .implemented_trait(db)?
+        .items(db)
+        .into_iter()
+        .find_map(|x| match x {
+            AssocItem::Function(f) if f.name(db) == name => Some(f),
+            _ => None,
+        })
+}
+
+/// Returns the macros an attribute belongs to, which is either the attribute macro itself or
+/// the derive macros whose helper it is
+pub(crate) fn get_attr_macros(semantics: &Semantics, attr: &Attr) -> Vec<Macro> {
+    let path = match attr.path() {
+        Some(x) => x,
+        None => return vec![],
+    };
+
+    match semantics.resolve_path(&path) {
+        Some(PathResolution::Def(ModuleDef::Macro(x))) => vec![x],
+        Some(PathResolution::DeriveHelper(x)) => vec![x.derive()],
+        Some(_) => vec![],
+        // Helper attributes on the deriving item itself are not resolved
+        None => attr
+            .syntax()
+            .parent()
+            .and_then(Adt::cast)
+            .into_iter()
+            .flat_map(|x| x.attrs())
+            .filter_map(|x| semantics.resolve_derive_macro(&x))
+            .flatten()
+            .flatten()
+            .collect(),
+    }
+}
+
+/// Returns whether the type implements the dependency trait with the given path
+pub(crate) fn implements(
+    semantics: &Semantics,
+    traits: &Table<Trait>,
+    ty: &Type,
+    trait_path: &str,
+) -> bool {
+    let db = semantics.db;
+
+    traits
+        .at(db, trait_path)
+        .into_iter()
+        .any(|t| ty.impls_trait(db, t, &[]))
+}
+
+/// Returns the type whose associated function is called with the path
+pub(crate) fn get_qualifier_type(semantics: &Semantics, path: &Path) -> Option<Type> {
+    match semantics.resolve_path(&path.qualifier()?)? {
+        PathResolution::Def(ModuleDef::Adt(x)) => Some(x.ty(semantics.db)),
+        PathResolution::Def(ModuleDef::TypeAlias(x)) => Some(x.ty(semantics.db)),
+        PathResolution::SelfType(x) => Some(x.self_ty(semantics.db)),
+        _ => None,
+    }
+}
+
+/// Same as `run_hooks` but for the hooks keyed by the trait the type of the node implements
+pub(crate) fn run_impl_hooks<N, NG, TG>(
+    upgrader: &mut Upgrader,
+    semantics: &Semantics,
+    traits: &Table<Trait>,
+    path_map: &Hooks<N>,
+    node: &N,
+    name_getter: NG,
+    type_getter: TG,
+) -> Option<()>
+where
+    NG: Fn(&N) -> Option<String>,
+    TG: Fn(&Semantics, &N) -> Option<Type>,
+{
+    let name = name_getter(node)?;
+
+    if !path_map.iter().any(|x| x.1.contains_key(&name)) {
+        return None;
+    }
+
+    let ty = type_getter(semantics, node)?;
+
+    for (trait_path, map) in path_map.iter() {
+        if let Some(hooks) = map.get(&name) {
+            if implements(semantics, traits, &ty, trait_path) {
+                for hook in hooks {
+                    hook.call(upgrader, node, semantics);
+                }
+            }
+        }
+    }
+
+    Some(())
+}
+
 pub(crate) fn run_hooks<'b, I, N, NG, PG>(
     upgrader: &mut Upgrader,
     semantics: &Semantics,
-    item_paths: &Map<I, String>,
+    item_paths: &Table<I>,
     path_map: &'b Hooks<N>,
     node: &N,
     name_getter: NG,
     path_getter: PG,
 ) -> Option<()>
 where
-    I: Eq + Debug,
+    I: Named + Debug,
     N: Debug,
     NG: Fn(&N) -> Option<String>,
     PG: Fn(&Semantics, &N) -> Option<I>,
@@ -47,12 +168,22 @@ where
     }
 
     let item = path_getter(semantics, node)?;
-    let path = item_paths.iter().find(|x| *x.0 == item)?.1;
-    let map = path_map.get(path)?;
-    let hooks = map.get(&name)?;
+    let paths = item_paths.paths_of(semantics.db, &item)?;
+    let mut parents = vec![];
+
+    for (parent, _) in paths.iter().filter_map(|x| x.rsplit_once("::")) {
+        if !parents.contains(&parent) {
+            parents.push(parent);
+        }
+    }
 
-    for hook in hooks {
-        hook(upgrader, node, semantics);
+    // The item can be hooked under the parent of any of the paths it can be reached at
+    for parent in parents {
+        if let Some(hooks) = path_map.get(parent).and_then(|x| x.get(&name)) {
+            for hook in hooks {
+                hook.call(upgrader, node, semantics);
+            }
+        }
     }
 
     Some(())
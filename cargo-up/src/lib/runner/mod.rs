@@ -1,15 +1,19 @@
 use crate::{
+    import::{add_imports, remove_unused_imports},
+    manifest::edit_manifest,
+    preloader::Preloader,
     ra_ap_syntax::AstNode,
-    semver::{Error as SemVerError, Version as SemverVersion},
+    semver::{BuildMetadata, Error as SemVerError, Version as SemverVersion},
+    upgrader::{Level, Note},
     utils::{normalize, Error, INTERNAL_ERR},
-    Semantics, Version,
+    Semantics, Upgrader, Version,
 };
 
 use log::{debug, info, trace};
-use oclif::term::{OUT_YELLOW, TERM_OUT};
+use oclif::term::{ERR_RED, ERR_YELLOW, OUT_YELLOW, TERM_ERR, TERM_OUT};
 use ra_ap_base_db::{FileId, SourceDatabase, SourceDatabaseExt};
 use ra_ap_hir::Crate;
-use ra_ap_ide_db::symbol_index::SymbolsDatabase;
+use ra_ap_ide_db::{symbol_index::SymbolsDatabase, Change, RootDatabase};
 use ra_ap_load_cargo::{load_workspace, LoadCargoConfig, ProcMacroServerChoice};
 use ra_ap_paths::AbsPathBuf;
 use ra_ap_project_model::{CargoConfig, ProjectManifest, ProjectWorkspace, RustLibSource};
@@ -19,19 +23,26 @@ use rust_visitor::Visitor;
 use std::{
     collections::HashMap as Map,
     fs::{read_to_string, write},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
 mod context;
+mod diff;
+mod expansion;
+mod format;
 mod helpers;
+mod report;
 mod visitor_impl;
 
 pub(crate) use context::Context;
+use format::format_changes;
+pub use format::Format;
+use report::{move_notes, todo_comment, Report};
 
 pub struct Runner {
     pub(crate) minimum: Option<SemverVersion>,
     pub(crate) versions: Vec<Version>,
-    version: SemverVersion,
+    current: usize,
 }
 
 impl Runner {
@@ -39,7 +50,7 @@ impl Runner {
         Self {
             minimum: None,
             versions: vec![],
-            version: SemverVersion::parse("0.0.0").expect(INTERNAL_ERR),
+            current: 0,
         }
     }
 
@@ -48,49 +59,104 @@ impl Runner {
         Ok(self)
     }
 
+    /// Adds the changes introduced at the version. Upgrading from `A` to `B` applies the
+    /// changes of every version in `(A, B]` in order, where a pre-release comes before
+    /// its release and build metadata is ignored.
     pub fn version(mut self, version: Version) -> Self {
         self.versions.push(version);
         self
     }
 }
 
+#[doc(hidden)]
+#[derive(Debug, Default)]
+pub struct RunOptions {
+    /// Print the changes as a unified diff instead of writing them
+    pub dry_run: bool,
+    /// Write the diff as a patch file instead of printing it
+    pub patch: Option<PathBuf>,
+    /// Remove the imports of the dependency which are left unused in the changed files
+    pub remove_unused_imports: bool,
+    /// Format the changed items or files with rustfmt
+    pub format: Option<Format>,
+    /// Fail instead of warning when a hook path or name does not match anything
+    pub strict: bool,
+    /// Directory to persist the preloaded indexes of the dependencies in across runs
+    pub cache: Option<PathBuf>,
+}
+
 impl Runner {
     fn get_version(&self) -> Option<&Version> {
-        self.versions.iter().find(|x| x.version == self.version)
+        self.versions.get(self.current)
+    }
+
+    /// Returns the indices of the versions after `from` up to `to`, in the order they
+    /// need to be applied. Versions with the same precedence keep their declared order.
+    fn versions_between(&self, from: &SemverVersion, to: &SemverVersion) -> Vec<usize> {
+        let (from, to) = (precedence(from), precedence(to));
+
+        let mut indices = (0..self.versions.len())
+            .filter(|x| {
+                let version = precedence(&self.versions[*x].version);
+                version > from && version <= to
+            })
+            .collect::<Vec<_>>();
+
+        indices.sort_by_key(|x| precedence(&self.versions[*x].version));
+        indices
     }
 }
 
+/// Returns the version without the build metadata, which does not take part in
+/// the precedence of versions
+fn precedence(version: &SemverVersion) -> SemverVersion {
+    let mut version = version.clone();
+    version.build = BuildMetadata::EMPTY;
+    version
+}
+
+/// Applies the changes of every version after `from` up to `to` in order, loading the
+/// workspace only once
 #[doc(hidden)]
-pub fn run(
+pub fn run_range(
+    root: &Path,
+    dep: &str,
+    runner: Runner,
+    from: SemverVersion,
+    to: SemverVersion,
+    options: RunOptions,
+) -> Result<(), Error> {
+    let versions = runner.versions_between(&from, &to);
+
+    if versions.is_empty() {
+        return Ok(TERM_OUT.write_line(&format!(
+            "Upgrader for crate {} has not described any changes after {} up to {} version",
+            OUT_YELLOW.apply_to(dep),
+            OUT_YELLOW.apply_to(from),
+            OUT_YELLOW.apply_to(to),
+        ))?);
+    }
+
+    upgrade(root, dep, runner, from, to, versions, options)
+}
+
+fn upgrade(
     root: &Path,
     dep: &str,
     mut runner: Runner,
     from: SemverVersion,
     to: SemverVersion,
+    versions: Vec<usize>,
+    options: RunOptions,
 ) -> Result<(), Error> {
     info!("Workspace root: {}", root.display());
 
     if let Some(min) = &runner.minimum {
-        if from < *min {
+        if precedence(&from) < precedence(min) {
             return Err(Error::NotMinimum(dep.into(), min.to_string()));
         }
     }
 
-    runner.version = to;
-    let version = runner.get_version();
-
-    let peers = if let Some(version) = version {
-        let mut peers = vec![dep.to_string()];
-        peers.extend(version.peers.clone());
-        peers
-    } else {
-        return Ok(TERM_OUT.write_line(&format!(
-            "Upgrader for crate {} has not described any changes for {} version",
-            OUT_YELLOW.apply_to(dep),
-            OUT_YELLOW.apply_to(runner.version),
-        ))?);
-    };
-
     // Loading project
     let manifest = ProjectManifest::discover_single(&AbsPathBuf::assert(root.into())).unwrap();
 
@@ -103,41 +169,288 @@ pub fn run(
     let bs = workspace.run_build_scripts(&cargo_config, no_progress)?;
     workspace.set_build_scripts(bs);
 
+    let mut manifests = vec![root.join("Cargo.toml")];
+    // Packages from a registry, whose sources can not change without their version changing
+    let mut registry = vec![];
+
+    if let ProjectWorkspace::Cargo { cargo, .. } = &workspace {
+        for pkg in cargo.packages().filter(|x| cargo[*x].is_member) {
+            let path: &Path = cargo[pkg].manifest.as_ref();
+
+            if !manifests.iter().any(|x| x == path) {
+                manifests.push(path.to_path_buf());
+            }
+        }
+
+        for pkg in cargo.packages() {
+            let id = &cargo[pkg].id;
+
+            if id.contains("registry+") || id.contains("sparse+") {
+                registry.push(normalize(&cargo[pkg].name));
+            }
+        }
+    }
+
     let load_cargo_config = LoadCargoConfig {
         load_out_dirs_from_check: true,
         prefill_caches: false,
         with_proc_macro_server: ProcMacroServerChoice::Sysroot,
     };
-    let (host, vfs, _) =
+    let (mut host, vfs, _) =
         load_workspace(workspace, &Default::default(), &load_cargo_config).unwrap();
 
-    // Preparing running wrapper
-    let db = host.raw_database();
-    let semantics = Semantics::new(db);
+    trace!("Crate graph: {:#?}", host.raw_database().crate_graph());
 
-    let mut changes = Map::<FileId, TextEdit>::new();
-    let mut context = Context::new(runner, semantics);
+    // Original and upgraded text of the changed files
+    let mut texts = Map::<FileId, (String, String)>::new();
+    let mut notes = Map::<FileId, Vec<Note>>::new();
+    let mut manifest_edits = vec![];
+    let mut preloader = Preloader::default();
 
-    // Run init hook
-    context.init(&from)?;
+    for current in versions {
+        info!("Upgrading to: {}", runner.versions[current].version);
+        runner.current = current;
 
-    trace!("Crate graph: {:#?}", db.crate_graph());
+        // Preparing running wrapper
+        let db = host.raw_database();
+        let semantics = Semantics::new(db);
 
-    // Loop to find and eager load the dep we are upgrading
-    for krate in Crate::all(db) {
-        if let Some(name) = krate.display_name(db) {
-            debug!("Checking if we need to preload: {}", name);
+        let mut context = Context::new(runner, preloader, semantics);
 
-            if let Some(peer) = peers
-                .iter()
-                .find(|x| **x == normalize(&format!("{}", name)))
-            {
-                context.preloader.load(peer, db, &krate);
+        // Run init hook
+        context.init(&from)?;
+
+        let version_def = context.runner.get_version().expect(INTERNAL_ERR);
+        let mut peers = vec![dep.to_string()];
+        peers.extend(version_def.peers.clone());
+        manifest_edits.extend(version_def.manifest.clone());
+
+        // Loop to find and eager load the dep we are upgrading
+        for krate in Crate::all(db) {
+            if let Some(name) = krate.display_name(db) {
+                debug!("Checking if we need to preload: {}", name);
+
+                if let Some(peer) = peers
+                    .iter()
+                    .find(|x| **x == normalize(&format!("{}", name)))
+                {
+                    context.preloader.load(
+                        peer,
+                        db,
+                        &krate,
+                        registry.contains(peer),
+                        options.cache.as_deref(),
+                    );
+                }
+            }
+        }
+
+        // Hooks on the dependency which would silently never run
+        let mut unmatched = vec![];
+
+        for (path, names) in version_def.hook_paths() {
+            if let Some(krate) = context.preloader.unmatched(path) {
+                unmatched.push(format!(
+                    "hook path `{}` does not match anything in {}",
+                    path, krate
+                ));
+                continue;
+            }
+
+            for name in names {
+                if let Some(krate) = context.preloader.unmatched(&format!("{}::{}", path, name)) {
+                    unmatched.push(format!(
+                        "hook name `{}` on `{}` does not match anything in {}",
+                        name, path, krate
+                    ));
+                }
             }
         }
+
+        for message in &unmatched {
+            let label = if options.strict {
+                ERR_RED.apply_to("error")
+            } else {
+                ERR_YELLOW.apply_to("warning")
+            };
+
+            TERM_ERR.write_line(&format!("{}: {}", label, message))?;
+        }
+
+        if options.strict && !unmatched.is_empty() {
+            return Err(Error::UnmatchedHooks(unmatched.len()));
+        }
+
+        let mut change = Change::new();
+
+        for (file_id, edit, file_notes) in walk(&mut context, db, root) {
+            debug!("Changes to be made: {:#?}", edit);
+
+            let mut moved = notes.remove(&file_id).unwrap_or_default();
+            moved.extend(file_notes);
+            move_notes(&mut moved, &edit);
+
+            if !moved.is_empty() {
+                notes.insert(file_id, moved);
+            }
+
+            if edit.is_empty() {
+                continue;
+            }
+
+            let (_, text) = match texts.get_mut(&file_id) {
+                Some(x) => x,
+                None => {
+                    let full_path = vfs.file_path(file_id);
+                    let full_path: &Path = full_path.as_path().expect(INTERNAL_ERR).as_ref();
+                    let file_text = read_to_string(full_path)?;
+
+                    texts
+                        .entry(file_id)
+                        .or_insert((file_text.clone(), file_text))
+                }
+            };
+
+            edit.apply(text);
+            change.change_file(file_id, Some(text.as_str().into()));
+        }
+
+        manifest_edits.extend(context.upgrader.finish_manifest());
+
+        let Context {
+            runner: next_runner,
+            preloader: next_preloader,
+            ..
+        } = context;
+
+        runner = next_runner;
+        preloader = next_preloader;
+
+        // Let the next version see the upgraded code
+        host.apply_change(change);
     }
 
-    // Actual loop to walk through the source code
+    if options.remove_unused_imports {
+        let db = host.raw_database();
+        let semantics = Semantics::new(db);
+
+        for (file_id, (_, text)) in texts.iter_mut() {
+            let source_file = semantics.parse(*file_id);
+
+            let mut upgrader = Upgrader::default();
+            remove_unused_imports(&mut upgrader, &semantics, source_file.syntax(), dep);
+
+            let edit = upgrader.finish();
+            debug!("Removing unused imports: {:#?}", edit);
+
+            if let Some(file_notes) = notes.get_mut(file_id) {
+                move_notes(file_notes, &edit);
+            }
+
+            edit.apply(text);
+        }
+    }
+
+    if let Some(format) = options.format {
+        let db = host.raw_database();
+
+        for (file_id, (file_text, new_text)) in texts.iter_mut() {
+            let edition = ra_ap_base_db::FileLoader::relevant_crates(db, *file_id)
+                .first()
+                .map_or("2018".to_string(), |x| {
+                    db.crate_graph()[*x].edition.to_string()
+                });
+
+            let full_path = vfs.file_path(*file_id);
+            let full_path: &Path = full_path.as_path().expect(INTERNAL_ERR).as_ref();
+            let dir = full_path.parent().unwrap_or(root);
+
+            let edit = format_changes(dir, format, &edition, file_text, new_text);
+            debug!("Formatting changes: {:#?}", edit);
+
+            if let Some(file_notes) = notes.get_mut(file_id) {
+                move_notes(file_notes, &edit);
+            }
+
+            edit.apply(new_text);
+        }
+    }
+
+    // Modify Cargo.toml
+    let mut files = vec![];
+
+    for full_path in manifests {
+        let file_text = read_to_string(&full_path)?;
+        let new_text = edit_manifest(&file_text, dep, &to, &manifest_edits)
+            .map_err(|err| Error::Manifest(full_path.display().to_string(), err))?;
+
+        files.push((full_path, file_text, new_text));
+    }
+
+    // Apply changes
+    let mut report = Report::default();
+
+    for (file_id, file_notes) in notes {
+        let full_path = vfs.file_path(file_id);
+        let full_path: &Path = full_path.as_path().expect(INTERNAL_ERR).as_ref();
+        let path = full_path.strip_prefix(root).unwrap_or(full_path);
+
+        match texts.get(&file_id) {
+            Some((_, text)) => report.add(path, text, file_notes),
+            None => report.add(path, &read_to_string(full_path)?, file_notes),
+        }
+    }
+
+    for (file_id, (file_text, new_text)) in texts {
+        let full_path = vfs.file_path(file_id);
+        let full_path: &Path = full_path.as_path().expect(INTERNAL_ERR).as_ref();
+
+        files.push((full_path.to_path_buf(), file_text, new_text));
+    }
+
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut patch = String::new();
+
+    for (full_path, file_text, new_text) in files {
+        if file_text == new_text {
+            continue;
+        }
+
+        if options.dry_run {
+            let path = full_path.strip_prefix(root).unwrap_or(&full_path);
+            patch.push_str(&diff::unified_diff(
+                &path.to_string_lossy(),
+                &file_text,
+                &new_text,
+            ));
+        } else {
+            write(&full_path, new_text)?;
+        }
+    }
+
+    if options.dry_run {
+        if let Some(path) = &options.patch {
+            write(path, patch)?;
+        } else {
+            TERM_OUT.write_str(&patch)?;
+        }
+    }
+
+    report.print()?;
+
+    Ok(())
+}
+
+/// Walks through the source code of the workspace, returning the changes for each file
+/// along with the notes left on it
+fn walk(
+    context: &mut Context,
+    db: &RootDatabase,
+    root: &Path,
+) -> Vec<(FileId, TextEdit, Vec<Note>)> {
+    let mut changes = vec![];
+
     for source_root_id in db.local_roots().iter() {
         let source_root = db.source_root(*source_root_id);
         let krates = db.source_root_crates(*source_root_id);
@@ -160,7 +473,7 @@ pub fn run(
         }
 
         for file_id in source_root.iter() {
-            let file = vfs.file_path(file_id);
+            let file = source_root.path_for_file(&file_id).expect(INTERNAL_ERR);
             info!("Walking: {}", file.as_path().expect(INTERNAL_ERR));
 
             let source_file = context.semantics.parse(file_id);
@@ -168,25 +481,33 @@ pub fn run(
 
             context.walk(source_file.syntax());
 
-            let edit = context.upgrader.finish();
-            debug!("Changes to be made: {:#?}", edit);
-
-            changes.insert(file_id, edit);
-        }
-    }
+            let imports = context.upgrader.finish_imports();
+            add_imports(
+                &mut context.upgrader,
+                &context.semantics,
+                source_file.syntax(),
+                imports,
+            );
+
+            let file_text = db.file_text(file_id);
+            let comments = context
+                .upgrader
+                .notes()
+                .iter()
+                .filter(|x| x.level == Level::Todo)
+                .map(|x| todo_comment(&file_text, x))
+                .collect::<Vec<_>>();
 
-    // Apply changes
-    for (file_id, edit) in changes {
-        let full_path = vfs.file_path(file_id);
-        let full_path = full_path.as_path().expect(INTERNAL_ERR);
+            for (offset, comment) in comments {
+                context.upgrader.insert(offset, comment);
+            }
 
-        let mut file_text = read_to_string(&full_path)?;
+            let edit = context.upgrader.finish();
+            let notes = context.upgrader.finish_notes();
 
-        edit.apply(&mut file_text);
-        write(&full_path, file_text)?;
+            changes.push((file_id, edit, notes));
+        }
     }
 
-    // TODO: Modify Cargo.toml
-
-    Ok(())
+    changes
 }
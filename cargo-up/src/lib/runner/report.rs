@@ -0,0 +1,113 @@
+use crate::{
+    ra_ap_syntax::{TextRange, TextSize},
+    upgrader::{Level, Note},
+};
+
+use oclif::term::{ERR_CYAN, ERR_YELLOW, TERM_ERR};
+use ra_ap_ide_db::line_index::LineIndex;
+use ra_ap_text_edit::TextEdit;
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Notes left by the upgrader which are printed at the end of the run
+#[derive(Debug, Default)]
+pub(crate) struct Report {
+    entries: Vec<(PathBuf, u32, u32, Note)>,
+}
+
+impl Report {
+    /// Adds the notes of the file, whose positions are in the given text
+    pub(crate) fn add(&mut self, path: &Path, text: &str, notes: Vec<Note>) {
+        let line_index = LineIndex::new(text);
+
+        for note in notes {
+            let line_col = line_index.line_col(note.range.start());
+
+            self.entries.push((
+                path.to_path_buf(),
+                line_col.line + 1,
+                line_col.col + 1,
+                note,
+            ));
+        }
+    }
+
+    pub(crate) fn print(mut self) -> io::Result<()> {
+        if self.entries.is_empty() {
+            return Ok(());
+        }
+
+        self.entries
+            .sort_by(|a, b| (&a.0, a.1, a.2).cmp(&(&b.0, b.1, b.2)));
+
+        TERM_ERR.write_line("")?;
+        TERM_ERR.write_line("The following need to be checked by hand:")?;
+
+        let mut current = None;
+
+        for (path, line, col, note) in &self.entries {
+            if current != Some(path) {
+                TERM_ERR.write_line("")?;
+                TERM_ERR.write_line(&path.display().to_string())?;
+                current = Some(path);
+            }
+
+            let level = match note.level {
+                Level::Warning => ERR_YELLOW.apply_to("warning"),
+                Level::Todo => ERR_CYAN.apply_to("todo"),
+            };
+
+            TERM_ERR.write_line(&format!("  {}:{} {}: {}", line, col, level, note.message))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the `// TODO(cargo-up): ..` comment to insert at the start of the line of the
+/// note, along with that offset
+pub(crate) fn todo_comment(text: &str, note: &Note) -> (TextSize, String) {
+    let start: usize = note.range.start().into();
+    let line_start = text[..start].rfind('\n').map_or(0, |x| x + 1);
+    let indent = text[line_start..]
+        .chars()
+        .take_while(|x| *x == ' ' || *x == '\t')
+        .collect::<String>();
+
+    (
+        TextSize::from(line_start as u32),
+        format!("{}// TODO(cargo-up): {}\n", indent, note.message),
+    )
+}
+
+/// Moves the notes to their positions in the edited text
+pub(crate) fn move_notes(notes: &mut [Note], edit: &TextEdit) {
+    for note in notes {
+        note.range = TextRange::empty(edited_offset(edit, note.range.start()));
+    }
+}
+
+/// Returns the offset in the edited text, which is after the text inserted at the offset
+/// or at the start of the replacement if the offset was replaced
+fn edited_offset(edit: &TextEdit, offset: TextSize) -> TextSize {
+    let mut moved = offset;
+
+    for indel in edit.iter() {
+        if indel.delete.start() > offset
+            || (indel.delete.start() == offset && !indel.delete.is_empty())
+        {
+            break;
+        }
+
+        if indel.delete.end() > offset {
+            return moved - (offset - indel.delete.start());
+        }
+
+        moved = moved + TextSize::of(indel.insert.as_str()) - indel.delete.len();
+    }
+
+    moved
+}
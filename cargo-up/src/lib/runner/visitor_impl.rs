@@ -1,27 +1,61 @@
 use crate::{
-    ra_ap_syntax::ast::{self, HasName},
+    attrs::derive_names,
+    ra_ap_syntax::{
+        ast::{self, AstNode, HasName},
+        SyntaxKind, SyntaxNode,
+    },
     runner::{
         context::Context,
         helpers::{
-            get_name, get_name_from_name, get_name_from_name_ref, get_name_from_path, run_hooks,
+            get_attr_macros, get_callee_path, get_name, get_name_from_name, get_name_from_name_ref,
+            get_name_from_path, get_qualifier_type, get_trait_function, implements, run_hooks,
+            run_impl_hooks,
         },
     },
     utils::INTERNAL_ERR,
 };
 
+use paste::paste;
 use ra_ap_hir::{Adt, ModuleDef, PathResolution};
-use rust_visitor::{Options, Visitor};
+use rust_visitor::{kinds, Options, Visitor};
+
+macro_rules! global_hooks {
+    ($($kind:ident,)*) => {
+        paste! {
+            /// Runs the hooks which are not filtered by path for every kind of node, skipping
+            /// the nodes generated by the macros being expanded
+            fn pre_visit(&mut self, node: &SyntaxNode) {
+                let version = self.runner.get_version().expect(INTERNAL_ERR);
+
+                match node.kind() {
+                    $(SyntaxKind::$kind => {
+                        let hooks = &version.[<hook_ $kind:lower>];
+
+                        if hooks.is_empty() || !self.is_written(node) {
+                            return;
+                        }
+
+                        let node = ast::[<$kind:camel>]::cast(node.clone()).expect(INTERNAL_ERR);
+
+                        for hook in hooks {
+                            hook.call(&mut self.upgrader, &node, &self.semantics);
+                        }
+                    },)*
+                    _ => {}
+                }
+            }
+        }
+    };
+}
 
 impl<'a> Visitor for Context<'a> {
+    kinds!(global_hooks);
+
     fn visit_source_file(&mut self, _: &ast::SourceFile, _: &mut Options) {}
 
     fn visit_method_call_expr(&mut self, method_call_expr: &ast::MethodCallExpr, _: &mut Options) {
         let version = self.runner.get_version().expect(INTERNAL_ERR);
 
-        for hook in &version.hook_method_call_expr {
-            hook(&mut self.upgrader, method_call_expr, &self.semantics);
-        }
-
         run_hooks(
             &mut self.upgrader,
             &self.semantics,
@@ -31,23 +65,135 @@ impl<'a> Visitor for Context<'a> {
             |n| get_name_from_name_ref(n.name_ref()),
             |s, n| s.resolve_method_call(n),
         );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.trait_methods,
+            &version.hook_method_call_expr_on,
+            method_call_expr,
+            |n| get_name_from_name_ref(n.name_ref()),
+            |s, n| get_trait_function(s.db, s.resolve_method_call(n)?),
+        );
+
+        run_impl_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.traits,
+            &version.hook_method_call_expr_on_impls,
+            method_call_expr,
+            |n| get_name_from_name_ref(n.name_ref()),
+            |s, n| Some(s.type_of_expr(&n.receiver()?)?.original.strip_references()),
+        );
     }
 
     fn visit_call_expr(&mut self, call_expr: &ast::CallExpr, _: &mut Options) {
         let version = self.runner.get_version().expect(INTERNAL_ERR);
 
-        for hook in &version.hook_call_expr {
-            hook(&mut self.upgrader, call_expr, &self.semantics);
-        }
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.functions,
+            &version.hook_call_expr_on,
+            call_expr,
+            |n| get_name_from_path(get_callee_path(n)),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Function(x))) =
+                    s.resolve_path(&get_callee_path(n)?)
+                {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.methods,
+            &version.hook_call_expr_on,
+            call_expr,
+            |n| get_name_from_path(get_callee_path(n)),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Function(x))) =
+                    s.resolve_path(&get_callee_path(n)?)
+                {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.trait_methods,
+            &version.hook_call_expr_on,
+            call_expr,
+            |n| get_name_from_path(get_callee_path(n)),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Function(x))) =
+                    s.resolve_path(&get_callee_path(n)?)
+                {
+                    get_trait_function(s.db, x)
+                } else {
+                    None
+                }
+            },
+        );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.structs,
+            &version.hook_call_expr_on,
+            call_expr,
+            |n| get_name_from_path(get_callee_path(n)),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Adt(Adt::Struct(x)))) =
+                    s.resolve_path(&get_callee_path(n)?)
+                {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.variants,
+            &version.hook_call_expr_on,
+            call_expr,
+            |n| get_name_from_path(get_callee_path(n)),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Variant(x))) =
+                    s.resolve_path(&get_callee_path(n)?)
+                {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
+
+        run_impl_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.traits,
+            &version.hook_call_expr_on_impls,
+            call_expr,
+            |n| get_name_from_path(get_callee_path(n)),
+            |s, n| get_qualifier_type(s, &get_callee_path(n)?),
+        );
     }
 
     fn visit_ident_pat(&mut self, ident_pat: &ast::IdentPat, _: &mut Options) {
         let version = self.runner.get_version().expect(INTERNAL_ERR);
 
-        for hook in &version.hook_ident_pat {
-            hook(&mut self.upgrader, ident_pat, &self.semantics);
-        }
-
         run_hooks(
             &mut self.upgrader,
             &self.semantics,
@@ -63,15 +209,27 @@ impl<'a> Visitor for Context<'a> {
                 }
             },
         );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.consts,
+            &version.hook_ident_pat_on,
+            ident_pat,
+            |n| get_name_from_name(n.name()),
+            |s, n| {
+                if let Some(ModuleDef::Const(x)) = s.resolve_bind_pat_to_const(n) {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
     }
 
     fn visit_path(&mut self, path: &ast::Path, _: &mut Options) {
         let version = self.runner.get_version().expect(INTERNAL_ERR);
 
-        for hook in &version.hook_path {
-            hook(&mut self.upgrader, path, &self.semantics);
-        }
-
         run_hooks(
             &mut self.upgrader,
             &self.semantics,
@@ -88,15 +246,140 @@ impl<'a> Visitor for Context<'a> {
                 }
             },
         );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.enums,
+            &version.hook_path_on,
+            path,
+            |n| get_name_from_path(Some(n.clone())),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Adt(Adt::Enum(x)))) = s.resolve_path(n) {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.unions,
+            &version.hook_path_on,
+            path,
+            |n| get_name_from_path(Some(n.clone())),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Adt(Adt::Union(x)))) = s.resolve_path(n)
+                {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.type_aliases,
+            &version.hook_path_on,
+            path,
+            |n| get_name_from_path(Some(n.clone())),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::TypeAlias(x))) = s.resolve_path(n) {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.consts,
+            &version.hook_path_on,
+            path,
+            |n| get_name_from_path(Some(n.clone())),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Const(x))) = s.resolve_path(n) {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.statics,
+            &version.hook_path_on,
+            path,
+            |n| get_name_from_path(Some(n.clone())),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Static(x))) = s.resolve_path(n) {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.macros,
+            &version.hook_path_on,
+            path,
+            |n| get_name_from_path(Some(n.clone())),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Macro(x))) = s.resolve_path(n) {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.traits,
+            &version.hook_path_on,
+            path,
+            |n| get_name_from_path(Some(n.clone())),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Trait(x))) = s.resolve_path(n) {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.functions,
+            &version.hook_path_on,
+            path,
+            |n| get_name_from_path(Some(n.clone())),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Function(x))) = s.resolve_path(n) {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
     }
 
     fn visit_path_expr(&mut self, path_expr: &ast::PathExpr, _: &mut Options) {
         let version = self.runner.get_version().expect(INTERNAL_ERR);
 
-        for hook in &version.hook_path_expr {
-            hook(&mut self.upgrader, path_expr, &self.semantics);
-        }
-
         run_hooks(
             &mut self.upgrader,
             &self.semantics,
@@ -115,6 +398,24 @@ impl<'a> Visitor for Context<'a> {
             },
         );
 
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.trait_methods,
+            &version.hook_path_expr_on,
+            path_expr,
+            |n| get_name_from_path(n.path()),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Function(x))) =
+                    s.resolve_path(&n.path().expect(INTERNAL_ERR))
+                {
+                    get_trait_function(s.db, x)
+                } else {
+                    None
+                }
+            },
+        );
+
         run_hooks(
             &mut self.upgrader,
             &self.semantics,
@@ -132,15 +433,47 @@ impl<'a> Visitor for Context<'a> {
                 }
             },
         );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.consts,
+            &version.hook_path_expr_on,
+            path_expr,
+            |n| get_name_from_path(n.path()),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Const(x))) =
+                    s.resolve_path(&n.path().expect(INTERNAL_ERR))
+                {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.statics,
+            &version.hook_path_expr_on,
+            path_expr,
+            |n| get_name_from_path(n.path()),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Static(x))) =
+                    s.resolve_path(&n.path().expect(INTERNAL_ERR))
+                {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
     }
 
     fn visit_path_pat(&mut self, path_pat: &ast::PathPat, _: &mut Options) {
         let version = self.runner.get_version().expect(INTERNAL_ERR);
 
-        for hook in &version.hook_path_pat {
-            hook(&mut self.upgrader, path_pat, &self.semantics);
-        }
-
         run_hooks(
             &mut self.upgrader,
             &self.semantics,
@@ -158,15 +491,29 @@ impl<'a> Visitor for Context<'a> {
                 }
             },
         );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.consts,
+            &version.hook_path_pat_on,
+            path_pat,
+            |n| get_name_from_path(n.path()),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Const(x))) =
+                    s.resolve_path(&n.path().expect(INTERNAL_ERR))
+                {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
     }
 
     fn visit_field_expr(&mut self, field_expr: &ast::FieldExpr, _: &mut Options) {
         let version = self.runner.get_version().expect(INTERNAL_ERR);
 
-        for hook in &version.hook_field_expr {
-            hook(&mut self.upgrader, field_expr, &self.semantics);
-        }
-
         run_hooks(
             &mut self.upgrader,
             &self.semantics,
@@ -181,10 +528,6 @@ impl<'a> Visitor for Context<'a> {
     fn visit_record_pat(&mut self, record_pat: &ast::RecordPat, _: &mut Options) {
         let version = self.runner.get_version().expect(INTERNAL_ERR);
 
-        for hook in &version.hook_record_pat {
-            hook(&mut self.upgrader, record_pat, &self.semantics);
-        }
-
         run_hooks(
             &mut self.upgrader,
             &self.semantics,
@@ -207,10 +550,6 @@ impl<'a> Visitor for Context<'a> {
     fn visit_record_expr(&mut self, record_expr: &ast::RecordExpr, _: &mut Options) {
         let version = self.runner.get_version().expect(INTERNAL_ERR);
 
-        for hook in &version.hook_record_expr {
-            hook(&mut self.upgrader, record_expr, &self.semantics);
-        }
-
         run_hooks(
             &mut self.upgrader,
             &self.semantics,
@@ -237,10 +576,6 @@ impl<'a> Visitor for Context<'a> {
     ) {
         let version = self.runner.get_version().expect(INTERNAL_ERR);
 
-        for hook in &version.hook_record_expr_field {
-            hook(&mut self.upgrader, record_expr_field, &self.semantics);
-        }
-
         run_hooks(
             &mut self.upgrader,
             &self.semantics,
@@ -261,10 +596,6 @@ impl<'a> Visitor for Context<'a> {
     fn visit_record_pat_field(&mut self, record_pat_field: &ast::RecordPatField, _: &mut Options) {
         let version = self.runner.get_version().expect(INTERNAL_ERR);
 
-        for hook in &version.hook_record_pat_field {
-            hook(&mut self.upgrader, record_pat_field, &self.semantics);
-        }
-
         run_hooks(
             &mut self.upgrader,
             &self.semantics,
@@ -279,10 +610,6 @@ impl<'a> Visitor for Context<'a> {
     fn visit_tuple_struct_pat(&mut self, tuple_struct_pat: &ast::TupleStructPat, _: &mut Options) {
         let version = self.runner.get_version().expect(INTERNAL_ERR);
 
-        for hook in &version.hook_tuple_struct_pat {
-            hook(&mut self.upgrader, tuple_struct_pat, &self.semantics);
-        }
-
         run_hooks(
             &mut self.upgrader,
             &self.semantics,
@@ -301,4 +628,211 @@ impl<'a> Visitor for Context<'a> {
             },
         );
     }
+
+    fn visit_fn(&mut self, function: &ast::Fn, _: &mut Options) {
+        let version = self.runner.get_version().expect(INTERNAL_ERR);
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.trait_methods,
+            &version.hook_fn_on,
+            function,
+            |n| get_name_from_name(n.name()),
+            |s, n| get_trait_function(s.db, s.to_def(n)?),
+        );
+    }
+
+    fn visit_path_type(&mut self, path_type: &ast::PathType, _: &mut Options) {
+        let version = self.runner.get_version().expect(INTERNAL_ERR);
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.structs,
+            &version.hook_path_type_on,
+            path_type,
+            |n| get_name_from_path(n.path()),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Adt(Adt::Struct(x)))) =
+                    s.resolve_path(&n.path()?)
+                {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.enums,
+            &version.hook_path_type_on,
+            path_type,
+            |n| get_name_from_path(n.path()),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Adt(Adt::Enum(x)))) =
+                    s.resolve_path(&n.path()?)
+                {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.unions,
+            &version.hook_path_type_on,
+            path_type,
+            |n| get_name_from_path(n.path()),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Adt(Adt::Union(x)))) =
+                    s.resolve_path(&n.path()?)
+                {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.type_aliases,
+            &version.hook_path_type_on,
+            path_type,
+            |n| get_name_from_path(n.path()),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::TypeAlias(x))) =
+                    s.resolve_path(&n.path()?)
+                {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.traits,
+            &version.hook_path_type_on,
+            path_type,
+            |n| get_name_from_path(n.path()),
+            |s, n| {
+                if let Some(PathResolution::Def(ModuleDef::Trait(x))) = s.resolve_path(&n.path()?) {
+                    Some(x)
+                } else {
+                    None
+                }
+            },
+        );
+    }
+
+    fn visit_struct(&mut self, struct_item: &ast::Struct, _: &mut Options) {
+        self.run_impl_of_hooks(&ast::Adt::Struct(struct_item.clone()));
+    }
+
+    fn visit_enum(&mut self, enum_item: &ast::Enum, _: &mut Options) {
+        self.run_impl_of_hooks(&ast::Adt::Enum(enum_item.clone()));
+    }
+
+    fn visit_union(&mut self, union_item: &ast::Union, _: &mut Options) {
+        self.run_impl_of_hooks(&ast::Adt::Union(union_item.clone()));
+    }
+
+    fn visit_impl(&mut self, impl_item: &ast::Impl, _: &mut Options) {
+        let version = self.runner.get_version().expect(INTERNAL_ERR);
+
+        // Implementations of traits
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.traits,
+            &version.hook_impl_on,
+            impl_item,
+            |n| match n.trait_()? {
+                ast::Type::PathType(x) => get_name_from_path(x.path()),
+                _ => None,
+            },
+            |s, n| s.to_def(n)?.trait_(s.db),
+        );
+    }
+
+    fn visit_macro_call(&mut self, macro_call: &ast::MacroCall, _: &mut Options) {
+        let version = self.runner.get_version().expect(INTERNAL_ERR);
+
+        run_hooks(
+            &mut self.upgrader,
+            &self.semantics,
+            &self.preloader.macros,
+            &version.hook_macro_call_on,
+            macro_call,
+            |n| get_name_from_path(n.path()),
+            |s, n| s.resolve_macro_call(n),
+        );
+
+        self.walk_expansion(macro_call);
+    }
+
+    fn visit_attr(&mut self, attr: &ast::Attr, _: &mut Options) {
+        let version = self.runner.get_version().expect(INTERNAL_ERR);
+
+        // Attribute macros and helper attributes of derive macros
+        for item in get_attr_macros(&self.semantics, attr) {
+            run_hooks(
+                &mut self.upgrader,
+                &self.semantics,
+                &self.preloader.macros,
+                &version.hook_attr_on,
+                attr,
+                |n| get_name_from_path(n.path()),
+                |_, _| Some(item),
+            );
+        }
+
+        // Each of the derive macros
+        if let Some(derives) = self.semantics.resolve_derive_macro(attr) {
+            for (name, derive) in derive_names(attr).into_iter().zip(derives) {
+                run_hooks(
+                    &mut self.upgrader,
+                    &self.semantics,
+                    &self.preloader.macros,
+                    &version.hook_attr_on,
+                    attr,
+                    |_| Some(name.text().to_string()),
+                    |_, _| derive,
+                );
+            }
+        }
+    }
+}
+
+impl<'a> Context<'a> {
+    fn run_impl_of_hooks(&mut self, adt: &ast::Adt) {
+        let version = self.runner.get_version().expect(INTERNAL_ERR);
+
+        if version.hook_impl_of.is_empty() {
+            return;
+        }
+
+        let ty = match self.semantics.to_def(adt) {
+            Some(x) => x.ty(self.semantics.db),
+            None => return,
+        };
+
+        for (trait_path, hooks) in &version.hook_impl_of {
+            if implements(&self.semantics, &self.preloader.traits, &ty, trait_path) {
+                for hook in hooks {
+                    hook.call(&mut self.upgrader, adt, &self.semantics);
+                }
+            }
+        }
+    }
 }
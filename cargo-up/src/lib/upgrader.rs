@@ -1,13 +1,44 @@
-use crate::ra_ap_syntax::{
-    ast::{Name, NameRef, Path, PathSegment},
-    AstNode,
+use crate::{
+    manifest::ManifestEdit,
+    ra_ap_syntax::{
+        ast::{Name, NameRef, Path, PathSegment},
+        AstNode,
+    },
 };
 
-use ra_ap_text_edit::{TextEdit, TextEditBuilder, TextRange, TextSize};
+use ra_ap_text_edit::{Indel, TextEdit, TextEditBuilder, TextRange, TextSize};
+
+use std::mem;
 
 #[derive(Default, Debug, Clone)]
 pub struct Upgrader {
-    edit: TextEditBuilder,
+    indels: Vec<(Indel, Origin)>,
+    imports: Vec<(TextRange, String)>,
+    manifest: Vec<ManifestEdit>,
+    notes: Vec<Note>,
+    /// Origin of the hook which is being run
+    pub(crate) origin: Origin,
+}
+
+/// The hook or rule a change comes from, along with its priority
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Origin {
+    pub(crate) name: String,
+    pub(crate) priority: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Level {
+    Warning,
+    Todo,
+}
+
+/// Something in the source which needs human attention after the upgrade
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Note {
+    pub(crate) level: Level,
+    pub(crate) range: TextRange,
+    pub(crate) message: String,
 }
 
 pub trait ToTextRange {
@@ -50,30 +81,214 @@ impl Upgrader {
         T: ToTextRange,
         S: Into<String>,
     {
-        self.edit.replace(range.to(), replace_with.into())
+        self.edit(Indel::replace(range.to(), replace_with.into()))
     }
 
     pub fn delete<T>(&mut self, range: T)
     where
         T: ToTextRange,
     {
-        self.edit.delete(range.to())
+        self.edit(Indel::delete(range.to()))
     }
 
     pub fn insert<S>(&mut self, offset: TextSize, text: S)
     where
         S: Into<String>,
     {
-        self.edit.insert(offset, text.into())
+        self.edit(Indel::insert(offset, text.into()))
+    }
+
+    fn edit(&mut self, indel: Indel) {
+        self.indels.push((indel, self.origin.clone()));
     }
 
-    pub fn add_dep(&mut self) {}
+    /// Import the item at the path in the module containing the range. Nothing is done if
+    /// it is already in scope there, otherwise it is merged into the existing imports.
+    pub fn ensure_import<T>(&mut self, range: T, path: &str)
+    where
+        T: ToTextRange,
+    {
+        self.imports.push((range.to(), path.to_string()));
+    }
 
-    pub fn add_feature(&mut self) {}
+    /// Report something which needs to be checked by hand at the end of the upgrade
+    pub fn warn<T, S>(&mut self, range: T, message: S)
+    where
+        T: ToTextRange,
+        S: Into<String>,
+    {
+        self.note(Level::Warning, range.to(), message.into());
+    }
+
+    /// Report a change which needs to be made by hand at the end of the upgrade and mark
+    /// it with a `// TODO(cargo-up): <message>` comment above the line
+    pub fn todo<T, S>(&mut self, range: T, message: S)
+    where
+        T: ToTextRange,
+        S: Into<String>,
+    {
+        self.note(Level::Todo, range.to(), message.into());
+    }
+
+    pub(crate) fn note(&mut self, level: Level, range: TextRange, message: String) {
+        let note = Note {
+            level,
+            range,
+            message,
+        };
+
+        // Macros can use the same argument more than once
+        if !self.notes.contains(&note) {
+            self.notes.push(note);
+        }
+    }
+
+    /// Add a dependency to the workspace members which depend on the upgraded crate
+    pub fn add_dep(&mut self, name: &str, version: &str) {
+        self.manifest.push(ManifestEdit::AddDep {
+            name: name.to_string(),
+            version: version.to_string(),
+        });
+    }
+
+    /// Remove a dependency from all the workspace members
+    pub fn remove_dep(&mut self, name: &str) {
+        self.manifest.push(ManifestEdit::RemoveDep {
+            name: name.to_string(),
+        });
+    }
+
+    /// Enable a feature of a dependency in all the workspace members which declare it
+    pub fn add_feature(&mut self, dep: &str, feature: &str) {
+        self.manifest.push(ManifestEdit::AddFeature {
+            dep: dep.to_string(),
+            feature: feature.to_string(),
+        });
+    }
+
+    /// Disable a feature of a dependency in all the workspace members which declare it
+    pub fn remove_feature(&mut self, dep: &str, feature: &str) {
+        self.manifest.push(ManifestEdit::RemoveFeature {
+            dep: dep.to_string(),
+            feature: feature.to_string(),
+        });
+    }
 
+    pub(crate) fn notes(&self) -> &[Note] {
+        &self.notes
+    }
+
+    /// Builds the edit out of the changes
     pub(crate) fn finish(&mut self) -> TextEdit {
-        let edit = self.edit.clone().finish();
-        self.edit = TextEditBuilder::default();
-        edit
+        let mut builder = TextEditBuilder::default();
+
+        for (indel, _) in self.finish_edits() {
+            builder.replace(indel.delete, indel.insert);
+        }
+
+        builder.finish()
+    }
+
+    /// Returns the changes which do not overlap along with their origins. When changes
+    /// overlap, the one with the highest priority is applied and the others are reported.
+    /// Between the same priorities, the outermost one (or the one made first for the same
+    /// range) is applied.
+    pub(crate) fn finish_edits(&mut self) -> Vec<(Indel, Origin)> {
+        let mut indels = mem::take(&mut self.indels);
+        indels.sort_by(|(a, _), (b, _)| {
+            a.delete
+                .start()
+                .cmp(&b.delete.start())
+                .then(b.delete.end().cmp(&a.delete.end()))
+        });
+
+        let mut applied: Vec<(Indel, Origin)> = vec![];
+
+        for (indel, origin) in indels {
+            if applied.iter().any(|x| x.0 == indel) {
+                continue;
+            }
+
+            let (overlapping, rest): (Vec<_>, Vec<_>) =
+                applied.into_iter().partition(|x| conflicts(&x.0, &indel));
+            applied = rest;
+
+            let wins = overlapping.iter().all(|x| x.1.priority < origin.priority);
+
+            if wins {
+                for other in overlapping {
+                    self.conflict(&(indel.clone(), origin.clone()), &other);
+                }
+
+                applied.push((indel, origin));
+            } else {
+                self.conflict(&overlapping[0], &(indel, origin));
+                applied.extend(overlapping);
+            }
+        }
+
+        applied
+    }
+
+    /// Reports the change which was not applied because of the other one
+    fn conflict(&mut self, applied: &(Indel, Origin), skipped: &(Indel, Origin)) {
+        let message = format!(
+            "Conflicting changes, applied the {} instead of the {}",
+            describe(applied),
+            describe(skipped)
+        );
+        self.note(Level::Warning, skipped.0.delete, message);
+    }
+
+    /// Adds a change made somewhere else, keeping its origin
+    pub(crate) fn extend_edits(&mut self, edits: Vec<(Indel, Origin)>) {
+        self.indels.extend(edits);
+    }
+
+    pub(crate) fn finish_imports(&mut self) -> Vec<(TextRange, String)> {
+        std::mem::take(&mut self.imports)
+    }
+
+    pub(crate) fn finish_manifest(&mut self) -> Vec<ManifestEdit> {
+        std::mem::take(&mut self.manifest)
+    }
+
+    pub(crate) fn finish_notes(&mut self) -> Vec<Note> {
+        std::mem::take(&mut self.notes)
+    }
+
+    pub(crate) fn extend_manifest(&mut self, edits: Vec<ManifestEdit>) {
+        self.manifest.extend(edits);
+    }
+}
+
+/// Returns whether both the changes can not be applied. Insertions at the same offset, or
+/// at the boundaries of a replaced range, can.
+fn conflicts(a: &Indel, b: &Indel) -> bool {
+    if a.delete.is_empty() && b.delete.is_empty() {
+        return false;
+    }
+
+    a.delete.start() < b.delete.end() && b.delete.start() < a.delete.end()
+}
+
+fn describe((indel, origin): &(Indel, Origin)) -> String {
+    let text = match indel.insert.split_once('\n') {
+        Some((line, _)) => format!("{} ..", line),
+        None => indel.insert.clone(),
+    };
+
+    let change = if indel.delete.is_empty() {
+        format!("insertion of `{}`", text)
+    } else if indel.insert.is_empty() {
+        "deletion".to_string()
+    } else {
+        format!("replacement with `{}`", text)
+    };
+
+    if origin.name.is_empty() {
+        change
+    } else {
+        format!("{} by `{}`", change, origin.name)
     }
 }
@@ -8,6 +8,10 @@ use std::io;
 pub enum Error {
     #[error("minimum version of {0} that should be upgraded from is {1}")]
     NotMinimum(String, String),
+    #[error("unable to edit manifest {0}, got {1}")]
+    Manifest(String, toml_edit::TomlError),
+    #[error("unmatched hooks of the upgrader in strict mode: {0}")]
+    UnmatchedHooks(usize),
     #[error("{0}")]
     Io(#[from] io::Error),
     #[error("{0}")]
@@ -21,6 +25,7 @@ impl CliError for Error {
                 ERR_YELLOW.apply_to(dep).to_string(),
                 ERR_YELLOW.apply_to(min).to_string(),
             ),
+            Self::Manifest(path, err) => Self::Manifest(ERR_YELLOW.apply_to(path).to_string(), err),
             _ => self,
         }
     }
@@ -1,19 +1,47 @@
 use crate::{
+    args::{edit_args, receiver_args, ArgEdit},
+    attrs::{attr_keys, derive_names, macros_at},
+    manifest::ManifestEdit,
     ra_ap_syntax::{
-        ast::{self, Expr, HasName, NameOrNameRef},
+        ast::{self, Expr, HasArgList, HasAttrs, HasName, NameOrNameRef},
         AstNode,
     },
+    relocate::{is_crate_path, move_use, relocate_path, relocate_use_tree, PathMap},
     semver::{Error as SemVerError, Version as SemverVersion},
+    upgrader::Origin,
     utils::{normalize, INTERNAL_ERR},
     Semantics, Upgrader,
 };
 
 use anyhow::Result as AnyResult;
 use paste::paste;
+use rust_visitor::kinds;
 
 use std::{collections::HashMap as Map, ops::Deref};
 
-pub(crate) type Hook<T> = Box<dyn Fn(&mut Upgrader, &T, &Semantics)>;
+pub(crate) struct Hook<T> {
+    origin: Origin,
+    f: Box<dyn Fn(&mut Upgrader, &T, &Semantics)>,
+}
+
+impl<T> Hook<T> {
+    fn new<F>(origin: Origin, f: F) -> Self
+    where
+        F: Fn(&mut Upgrader, &T, &Semantics) + 'static,
+    {
+        Self {
+            origin,
+            f: Box::new(f),
+        }
+    }
+
+    /// Runs the hook, marking the changes made by it with its origin
+    pub(crate) fn call(&self, upgrader: &mut Upgrader, node: &T, semantics: &Semantics) {
+        let outer = std::mem::replace(&mut upgrader.origin, self.origin.clone());
+        (self.f)(upgrader, node, semantics);
+        upgrader.origin = outer;
+    }
+}
 
 pub(crate) struct Hooks<T>(Map<String, Map<String, Vec<Hook<T>>>>);
 
@@ -50,16 +78,59 @@ impl<T> Hooks<T> {
     }
 }
 
+/// Calls the macro with the kinds of nodes resolving to a definition, which are the ones
+/// dispatched to `_on` hooks in the runner, followed by the given tokens
+macro_rules! resolvable_kinds {
+    ($callback:ident, $($args:tt)*) => {
+        $callback!(
+            [
+                ATTR,
+                CALL_EXPR,
+                FIELD_EXPR,
+                FN,
+                IDENT_PAT,
+                IMPL,
+                MACRO_CALL,
+                METHOD_CALL_EXPR,
+                PATH,
+                PATH_EXPR,
+                PATH_PAT,
+                PATH_TYPE,
+                RECORD_EXPR,
+                RECORD_EXPR_FIELD,
+                RECORD_PAT,
+                RECORD_PAT_FIELD,
+                TUPLE_STRUCT_PAT,
+            ],
+            $($args)*
+        );
+    };
+}
+
 macro_rules! members {
-    ($($node:ident,)*) => {
+    ($($kind:ident,)*) => {
+        resolvable_kinds!(members_with_on, $($kind,)*);
+    };
+}
+
+macro_rules! members_with_on {
+    ([$($on:ident,)*], $($kind:ident,)*) => {
         paste! {
             pub struct Version {
                 pub(crate) version: SemverVersion,
                 pub(crate) peers: Vec<String>,
+                pub(crate) manifest: Vec<ManifestEdit>,
                 pub(crate) init: Option<Box<dyn Fn(&mut Upgrader, &SemverVersion) -> AnyResult<()>>>,
+                rule: Option<&'static str>,
+                priority: i32,
+                pub(crate) hook_impl_of: Map<String, Vec<Hook<ast::Adt>>>,
+                pub(crate) hook_method_call_expr_on_impls: Hooks<ast::MethodCallExpr>,
+                pub(crate) hook_call_expr_on_impls: Hooks<ast::CallExpr>,
+                $(
+                    pub(crate) [<hook_ $kind:lower>]: Vec<Hook<ast::[<$kind:camel>]>>,
+                )*
                 $(
-                    pub(crate) [<hook_ $node:snake>]: Vec<Hook<ast::$node>>,
-                    pub(crate) [<hook_ $node:snake _on>]: Hooks<ast::$node>,
+                    pub(crate) [<hook_ $on:lower _on>]: Hooks<ast::[<$on:camel>]>,
                 )*
             }
 
@@ -68,35 +139,94 @@ macro_rules! members {
                     Ok(Self {
                         version: SemverVersion::parse(version)?,
                         peers: vec![],
+                        manifest: vec![],
                         init: None,
+                        rule: None,
+                        priority: 0,
+                        hook_impl_of: Map::new(),
+                        hook_method_call_expr_on_impls: Hooks::default(),
+                        hook_call_expr_on_impls: Hooks::default(),
                         $(
-                            [<hook_ $node:snake>]: Vec::new(),
-                            [<hook_ $node:snake _on>]: Hooks::default(),
+                            [<hook_ $kind:lower>]: Vec::new(),
+                        )*
+                        $(
+                            [<hook_ $on:lower _on>]: Hooks::default(),
                         )*
                     })
                 }
+
+                /// Paths the hooks on dependency items are registered with, along with the
+                /// names of the items hooked under them
+                pub(crate) fn hook_paths(&self) -> Vec<(&String, Vec<&String>)> {
+                    let mut paths = self
+                        .hook_impl_of
+                        .keys()
+                        .chain(self.hook_method_call_expr_on_impls.keys())
+                        .chain(self.hook_call_expr_on_impls.keys())
+                        .map(|x| (x, vec![]))
+                        .collect::<Vec<_>>();
+
+                    $(
+                        for (path, names) in self.[<hook_ $on:lower _on>].iter() {
+                            // Helper attributes are not items of the dependency
+                            if stringify!($on) == "ATTR" {
+                                paths.push((path, vec![]));
+                            } else {
+                                paths.push((path, names.keys().collect()));
+                            }
+                        }
+                    )*
+
+                    paths.sort();
+
+                    let mut merged: Vec<(&String, Vec<&String>)> = vec![];
+
+                    for (path, names) in paths {
+                        match merged.last_mut() {
+                            Some(last) if last.0 == path => last.1.extend(names),
+                            _ => merged.push((path, names)),
+                        }
+                    }
+
+                    for (_, names) in merged.iter_mut() {
+                        names.sort();
+                        names.dedup();
+                    }
+
+                    merged
+                }
             }
         }
     };
 }
 
 macro_rules! methods {
-    ($($node:ident,)*) => {
+    ($($kind:ident,)*) => {
         paste! {
             $(
-                pub fn [<hook_ $node:snake>]<F>(mut self, f: F) -> Self
+                pub fn [<hook_ $kind:lower>]<F>(mut self, f: F) -> Self
                 where
-                    F: Fn(&mut Upgrader, &ast::$node, &Semantics) + 'static,
+                    F: Fn(&mut Upgrader, &ast::[<$kind:camel>], &Semantics) + 'static,
                 {
-                    self.[<hook_ $node:snake>].push(Box::new(f));
+                    let origin = self.origin(stringify!([<hook_ $kind:lower>]));
+                    self.[<hook_ $kind:lower>].push(Hook::new(origin, f));
                     self
                 }
+            )*
+        }
+    };
+}
 
-                pub fn [<hook_ $node:snake _on>]<F>(mut self, path: &str, name: &str, f: F) -> Self
+macro_rules! methods_on {
+    ([$($kind:ident,)*],) => {
+        paste! {
+            $(
+                pub fn [<hook_ $kind:lower _on>]<F>(mut self, path: &str, name: &str, f: F) -> Self
                 where
-                    F: Fn(&mut Upgrader, &ast::$node, &Semantics) + 'static
+                    F: Fn(&mut Upgrader, &ast::[<$kind:camel>], &Semantics) + 'static
                 {
-                    self.[<hook_ $node:snake _on>].insert(path, name, Box::new(f));
+                    let origin = self.origin(stringify!([<hook_ $kind:lower _on>]));
+                    self.[<hook_ $kind:lower _on>].insert(path, name, Hook::new(origin, f));
                     self
                 }
             )*
@@ -104,20 +234,7 @@ macro_rules! methods {
     };
 }
 
-members!(
-    MethodCallExpr,
-    CallExpr,
-    IdentPat,
-    Path,
-    PathExpr,
-    PathPat,
-    FieldExpr,
-    RecordPat,
-    RecordExpr,
-    RecordExprField,
-    RecordPatField,
-    TupleStructPat,
-);
+kinds!(members);
 
 impl Version {
     pub fn peers(mut self, peers: &[&str]) -> Self {
@@ -133,100 +250,463 @@ impl Version {
         self
     }
 
-    pub fn rename_structs(mut self, name: &str, map: &'static [[&str; 2]]) -> Self {
-        for rename in map.into_iter() {
-            self = self.hook_path_on(name, rename[0], move |u, n, _| {
-                u.replace(n.segment(), rename[1]);
-            })
+    /// Sets the priority of the hooks and rules added after it, which is `0` by default.
+    /// When their changes overlap, the one with the highest priority is applied.
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    fn origin(&self, hook: &str) -> Origin {
+        Origin {
+            name: self.rule.unwrap_or(hook).to_string(),
+            priority: self.priority,
         }
+    }
 
+    /// Marks the hooks added by the rule with its name
+    fn rule<F>(mut self, rule: &'static str, f: F) -> Self
+    where
+        F: FnOnce(Self) -> Self,
+    {
+        self.rule = Some(rule);
+
+        let mut version = f(self);
+        version.rule = None;
+        version
+    }
+
+    /// Hook on the definitions of the types implementing the trait, either manually or
+    /// with a derive
+    pub fn hook_impl_of<F>(mut self, trait_path: &str, f: F) -> Self
+    where
+        F: Fn(&mut Upgrader, &ast::Adt, &Semantics) + 'static,
+    {
+        let origin = self.origin("hook_impl_of");
+        self.hook_impl_of
+            .entry(trait_path.to_string())
+            .or_default()
+            .push(Hook::new(origin, f));
         self
     }
 
-    pub fn rename_methods(mut self, name: &str, map: &'static [[&str; 2]]) -> Self {
-        for rename in map.into_iter() {
-            self = self
-                .hook_method_call_expr_on(name, rename[0], move |u, n, _| {
-                    u.replace(n.name_ref(), rename[1]);
-                })
-                .hook_path_expr_on(name, rename[0], move |u, n, _| {
-                    u.replace(n.path(), rename[1]);
-                });
-        }
+    /// Hook on the method calls whose receiver implements the trait
+    pub fn hook_method_call_expr_on_impls<F>(mut self, trait_path: &str, name: &str, f: F) -> Self
+    where
+        F: Fn(&mut Upgrader, &ast::MethodCallExpr, &Semantics) + 'static,
+    {
+        let origin = self.origin("hook_method_call_expr_on_impls");
+        self.hook_method_call_expr_on_impls
+            .insert(trait_path, name, Hook::new(origin, f));
+        self
+    }
 
+    /// Hook on the associated function calls (`Type::name(..)`) of the types implementing
+    /// the trait
+    pub fn hook_call_expr_on_impls<F>(mut self, trait_path: &str, name: &str, f: F) -> Self
+    where
+        F: Fn(&mut Upgrader, &ast::CallExpr, &Semantics) + 'static,
+    {
+        let origin = self.origin("hook_call_expr_on_impls");
+        self.hook_call_expr_on_impls
+            .insert(trait_path, name, Hook::new(origin, f));
         self
     }
 
-    pub fn rename_members(mut self, name: &str, map: &'static [[&str; 2]]) -> Self {
-        for rename in map.into_iter() {
-            self = self
-                .hook_field_expr_on(name, rename[0], move |u, n, _| {
-                    u.replace(n.name_ref(), rename[1]);
-                })
-                .hook_record_pat_field_on(name, rename[0], move |u, n, _| match n.field_name() {
-                    Some(NameOrNameRef::Name(_)) => {
-                        u.replace(n.syntax().text_range(), format!("{}: {}", rename[1], n))
-                    }
-                    Some(NameOrNameRef::NameRef(name_ref)) => {
-                        u.replace(name_ref.syntax().text_range(), rename[1])
+    pub fn replace_dep(
+        mut self,
+        old: &str,
+        new: &str,
+        version: &str,
+        features: &[&str],
+        map: &'static [[&str; 2]],
+    ) -> Self {
+        self.manifest.push(ManifestEdit::AddDep {
+            name: new.to_string(),
+            version: version.to_string(),
+        });
+
+        for feature in features {
+            self.manifest.push(ManifestEdit::AddFeature {
+                dep: new.to_string(),
+                feature: feature.to_string(),
+            });
+        }
+
+        self.manifest.push(ManifestEdit::RemoveDep {
+            name: old.to_string(),
+        });
+
+        let old = normalize(old);
+        let new = normalize(new);
+
+        let mut paths: PathMap = map
+            .iter()
+            .map(|x| (x[0].to_string(), x[1].to_string()))
+            .collect();
+        paths.push((old.clone(), new.clone()));
+
+        let (use_old, use_paths) = (old.clone(), paths.clone());
+        let (path_old, path_paths) = (old.clone(), paths);
+        let path_crates = vec![old.clone()];
+
+        self.rule("replace_dep", move |version| {
+            version
+                .hook_use(move |u, n, s| {
+                    if let Some(tree) = n.use_tree() {
+                        if tree.path().is_none_or(|x| is_crate_path(s, &x, &use_old)) {
+                            relocate_use_tree(u, &tree, &use_paths);
+                        }
                     }
-                    _ => {}
                 })
-                .hook_record_expr_field_on(name, rename[0], move |u, n, _| {
+                .hook_extern_crate(move |u, n, _| {
                     if let Some(name_ref) = n.name_ref() {
-                        u.replace(name_ref.syntax().text_range(), rename[1]);
-                    } else if let Some(Expr::PathExpr(path_expr)) = n.expr() {
-                        u.replace(
-                            path_expr.syntax().text_range(),
-                            format!("{}: {}", rename[1], rename[0]),
-                        );
+                        if normalize(&name_ref.text()) == old {
+                            u.replace(name_ref.syntax().text_range(), new.clone());
+                        }
                     }
-                });
-        }
+                })
+                .hook_path(move |u, n, s| {
+                    let is_import = n.syntax().ancestors().any(|x| ast::Use::can_cast(x.kind()));
 
-        self
+                    if n.parent_path().is_none() && !is_import && is_crate_path(s, n, &path_old) {
+                        relocate_path(u, s, n, &path_paths, &path_crates);
+                    }
+                })
+        })
     }
 
-    pub fn rename_variants(mut self, name: &str, map: &'static [[&str; 2]]) -> Self {
-        for rename in map.into_iter() {
-            self = self
-                .hook_path_expr_on(name, rename[0], move |u, n, _| {
-                    u.replace(n.path(), rename[1]);
+    pub fn move_items(self, map: &'static [[&str; 2]]) -> Self {
+        let paths: PathMap = map
+            .iter()
+            .map(|x| (x[0].to_string(), x[1].to_string()))
+            .collect();
+
+        let mut crates: Vec<String> = vec![];
+
+        for item in map.iter() {
+            let name = normalize(item[0].split("::").next().expect(INTERNAL_ERR));
+
+            if !crates.contains(&name) {
+                crates.push(name);
+            }
+        }
+
+        let (use_crates, use_paths) = (crates.clone(), paths.clone());
+
+        self.rule("move_items", move |version| {
+            version
+                .hook_use(move |u, n, s| {
+                    move_use(u, s, n, &use_paths, &use_crates);
                 })
-                .hook_path_pat_on(name, rename[0], move |u, n, _| {
-                    u.replace(n.path(), rename[1]);
+                .hook_path(move |u, n, s| {
+                    let is_import = n.syntax().ancestors().any(|x| ast::Use::can_cast(x.kind()));
+
+                    if n.parent_path().is_none() && !is_import {
+                        relocate_path(u, s, n, &paths, &crates);
+                    }
                 })
-                .hook_record_pat_on(name, rename[0], move |u, n, _| {
-                    u.replace(n.path(), rename[1]);
+        })
+    }
+
+    pub fn reorder_args(self, path: &str, name: &str, order: &'static [usize]) -> Self {
+        self.edit_args(path, name, ArgEdit::Reorder(order))
+    }
+
+    pub fn insert_arg(self, path: &str, name: &str, index: usize, expr: &'static str) -> Self {
+        self.edit_args(path, name, ArgEdit::Insert(index, expr))
+    }
+
+    pub fn remove_arg(self, path: &str, name: &str, index: usize) -> Self {
+        self.edit_args(path, name, ArgEdit::Remove(index))
+    }
+
+    /// Wraps the argument with the template, in which `{}` is replaced by the argument
+    pub fn wrap_arg(self, path: &str, name: &str, index: usize, template: &'static str) -> Self {
+        self.edit_args(path, name, ArgEdit::Wrap(index, template))
+    }
+
+    fn edit_args(self, path: &str, name: &str, edit: ArgEdit) -> Self {
+        let rule = match edit {
+            ArgEdit::Reorder(_) => "reorder_args",
+            ArgEdit::Insert(..) => "insert_arg",
+            ArgEdit::Remove(_) => "remove_arg",
+            ArgEdit::Wrap(..) => "wrap_arg",
+        };
+        let method_edit = edit.clone();
+
+        self.rule(rule, |version| {
+            version
+                .hook_method_call_expr_on(path, name, move |u, n, _| {
+                    if let Some(arg_list) = n.arg_list() {
+                        edit_args(u, &arg_list, 0, &method_edit);
+                    }
                 })
-                .hook_record_expr_on(name, rename[0], move |u, n, _| {
-                    u.replace(n.path(), rename[1]);
+                .hook_call_expr_on(path, name, move |u, n, s| {
+                    if let Some(arg_list) = n.arg_list() {
+                        edit_args(u, &arg_list, receiver_args(s, n), &edit);
+                    }
                 })
-                .hook_tuple_struct_pat_on(name, rename[0], move |u, n, _| {
-                    u.replace(n.path(), rename[1]);
+        })
+    }
+
+    fn rename_paths(self, rule: &'static str, name: &str, map: &'static [[&str; 2]]) -> Self {
+        self.rule(rule, |mut version| {
+            for rename in map.iter() {
+                version = version.hook_path_on(name, rename[0], move |u, n, _| {
+                    u.replace(n.segment(), rename[1]);
                 })
-                .hook_ident_pat_on(name, rename[0], move |u, n, _| {
-                    u.replace(n.name(), rename[1]);
+            }
+
+            version
+        })
+    }
+
+    pub fn rename_structs(self, name: &str, map: &'static [[&str; 2]]) -> Self {
+        self.rename_paths("rename_structs", name, map)
+    }
+
+    pub fn rename_enums(self, name: &str, map: &'static [[&str; 2]]) -> Self {
+        self.rename_paths("rename_enums", name, map)
+    }
+
+    pub fn rename_unions(self, name: &str, map: &'static [[&str; 2]]) -> Self {
+        self.rename_paths("rename_unions", name, map)
+    }
+
+    pub fn rename_type_aliases(self, name: &str, map: &'static [[&str; 2]]) -> Self {
+        self.rename_paths("rename_type_aliases", name, map)
+    }
+
+    pub fn rename_consts(self, name: &str, map: &'static [[&str; 2]]) -> Self {
+        self.rule("rename_consts", |mut version| {
+            for rename in map.iter() {
+                version = version
+                    .hook_path_on(name, rename[0], move |u, n, _| {
+                        u.replace(n.segment(), rename[1]);
+                    })
+                    .hook_ident_pat_on(name, rename[0], move |u, n, _| {
+                        u.replace(n.name(), rename[1]);
+                    });
+            }
+
+            version
+        })
+    }
+
+    pub fn rename_statics(self, name: &str, map: &'static [[&str; 2]]) -> Self {
+        self.rename_paths("rename_statics", name, map)
+    }
+
+    pub fn rename_macros(self, name: &str, map: &'static [[&str; 2]]) -> Self {
+        self.rule("rename_macros", |mut version| {
+            for rename in map.iter() {
+                version = version
+                    .hook_macro_call_on(name, rename[0], move |u, n, _| {
+                        u.replace(n.path(), rename[1]);
+                    })
+                    .hook_path_on(name, rename[0], move |u, n, _| {
+                        if n.syntax().ancestors().any(|x| ast::Use::can_cast(x.kind())) {
+                            u.replace(n.segment(), rename[1]);
+                        }
+                    });
+            }
+
+            let krate = normalize(name.split("::").next().expect(INTERNAL_ERR));
+
+            // Macros listed in `#[macro_use(..)] extern crate`
+            version.hook_extern_crate(move |u, n, _| {
+                if n.name_ref().is_none_or(|x| normalize(&x.text()) != krate) {
+                    return;
+                }
+
+                for attr in n.attrs() {
+                    let meta = match attr.meta() {
+                        Some(x) if x.path().is_some_and(|x| x.to_string() == "macro_use") => x,
+                        _ => continue,
+                    };
+
+                    let tokens = meta
+                        .token_tree()
+                        .into_iter()
+                        .flat_map(|x| x.syntax().children_with_tokens())
+                        .filter_map(|x| x.into_token());
+
+                    for token in tokens {
+                        if let Some(rename) = map.iter().find(|x| token.text() == x[0]) {
+                            u.replace(token.text_range(), rename[1]);
+                        }
+                    }
+                }
+            })
+        })
+    }
+
+    pub fn rename_derives(self, name: &str, map: &'static [[&str; 2]]) -> Self {
+        self.rule("rename_derives", |mut version| {
+            for rename in map.iter() {
+                let path = format!("{}::{}", name, rename[0]);
+
+                version = version
+                    .hook_attr_on(name, rename[0], move |u, n, s| {
+                        let derives = s.resolve_derive_macro(n).unwrap_or_default();
+                        let macros = macros_at(s, &path);
+
+                        // Other derives can be written with the same name
+                        for (token, derive) in derive_names(n).into_iter().zip(derives) {
+                            if token.text() == rename[0]
+                                && derive.is_some_and(|x| macros.contains(&x))
+                            {
+                                u.replace(token.text_range(), rename[1]);
+                            }
+                        }
+                    })
+                    .hook_path_on(name, rename[0], move |u, n, _| {
+                        if n.syntax().ancestors().any(|x| ast::Use::can_cast(x.kind())) {
+                            u.replace(n.segment(), rename[1]);
+                        }
+                    });
+            }
+
+            version
+        })
+    }
+
+    /// Renames helper attributes and the keys inside them, which are written as
+    /// `attr::key` or `attr::key::nested`.
+    pub fn rename_attribute_keys(self, name: &str, map: &'static [[&str; 2]]) -> Self {
+        self.rule("rename_attribute_keys", |mut version| {
+            let mut attrs = vec![];
+
+            for rename in map.iter() {
+                let attr = rename[0].split("::").next().expect(INTERNAL_ERR);
+
+                if !attrs.contains(&attr) {
+                    attrs.push(attr);
+                }
+            }
+
+            for attr in attrs {
+                version = version.hook_attr_on(name, attr, move |u, n, _| {
+                    for (key, token) in attr_keys(n) {
+                        if let Some(rename) = map.iter().find(|x| x[0] == key) {
+                            u.replace(token.text_range(), rename[1]);
+                        }
+                    }
                 });
-        }
+            }
 
-        self
+            version
+        })
+    }
+
+    pub fn rename_functions(self, name: &str, map: &'static [[&str; 2]]) -> Self {
+        self.rename_paths("rename_functions", name, map)
+    }
+
+    pub fn rename_traits(self, name: &str, map: &'static [[&str; 2]]) -> Self {
+        self.rename_paths("rename_traits", name, map)
+    }
+
+    pub fn rename_trait_methods(self, name: &str, map: &'static [[&str; 2]]) -> Self {
+        self.rule("rename_trait_methods", |mut version| {
+            for rename in map.iter() {
+                version = version
+                    .hook_method_call_expr_on(name, rename[0], move |u, n, _| {
+                        u.replace(n.name_ref(), rename[1]);
+                    })
+                    .hook_path_expr_on(name, rename[0], move |u, n, _| {
+                        u.replace(n.path(), rename[1]);
+                    })
+                    .hook_fn_on(name, rename[0], move |u, n, _| {
+                        u.replace(n.name(), rename[1]);
+                    });
+            }
+
+            version
+        })
+    }
+
+    pub fn rename_methods(self, name: &str, map: &'static [[&str; 2]]) -> Self {
+        self.rule("rename_methods", |mut version| {
+            for rename in map.into_iter() {
+                version = version
+                    .hook_method_call_expr_on(name, rename[0], move |u, n, _| {
+                        u.replace(n.name_ref(), rename[1]);
+                    })
+                    .hook_path_expr_on(name, rename[0], move |u, n, _| {
+                        u.replace(n.path(), rename[1]);
+                    });
+            }
+
+            version
+        })
+    }
+
+    pub fn rename_members(self, name: &str, map: &'static [[&str; 2]]) -> Self {
+        self.rule("rename_members", |mut version| {
+            for rename in map.into_iter() {
+                version = version
+                    .hook_field_expr_on(name, rename[0], move |u, n, _| {
+                        u.replace(n.name_ref(), rename[1]);
+                    })
+                    .hook_record_pat_field_on(name, rename[0], move |u, n, _| {
+                        match n.field_name() {
+                            Some(NameOrNameRef::Name(_)) => {
+                                u.replace(n.syntax().text_range(), format!("{}: {}", rename[1], n))
+                            }
+                            Some(NameOrNameRef::NameRef(name_ref)) => {
+                                u.replace(name_ref.syntax().text_range(), rename[1])
+                            }
+                            _ => {}
+                        }
+                    })
+                    .hook_record_expr_field_on(name, rename[0], move |u, n, _| {
+                        if let Some(name_ref) = n.name_ref() {
+                            u.replace(name_ref.syntax().text_range(), rename[1]);
+                        } else if let Some(Expr::PathExpr(path_expr)) = n.expr() {
+                            u.replace(
+                                path_expr.syntax().text_range(),
+                                format!("{}: {}", rename[1], rename[0]),
+                            );
+                        }
+                    });
+            }
+
+            version
+        })
+    }
+
+    pub fn rename_variants(self, name: &str, map: &'static [[&str; 2]]) -> Self {
+        self.rule("rename_variants", |mut version| {
+            for rename in map.into_iter() {
+                version = version
+                    .hook_path_expr_on(name, rename[0], move |u, n, _| {
+                        u.replace(n.path(), rename[1]);
+                    })
+                    .hook_path_pat_on(name, rename[0], move |u, n, _| {
+                        u.replace(n.path(), rename[1]);
+                    })
+                    .hook_record_pat_on(name, rename[0], move |u, n, _| {
+                        u.replace(n.path(), rename[1]);
+                    })
+                    .hook_record_expr_on(name, rename[0], move |u, n, _| {
+                        u.replace(n.path(), rename[1]);
+                    })
+                    .hook_tuple_struct_pat_on(name, rename[0], move |u, n, _| {
+                        u.replace(n.path(), rename[1]);
+                    })
+                    .hook_ident_pat_on(name, rename[0], move |u, n, _| {
+                        u.replace(n.name(), rename[1]);
+                    });
+            }
+
+            version
+        })
     }
 }
 
 impl Version {
-    methods!(
-        MethodCallExpr,
-        CallExpr,
-        IdentPat,
-        Path,
-        PathExpr,
-        PathPat,
-        FieldExpr,
-        RecordPat,
-        RecordExpr,
-        RecordExprField,
-        RecordPatField,
-        TupleStructPat,
-    );
+    kinds!(methods);
+    resolvable_kinds!(methods_on,);
 }
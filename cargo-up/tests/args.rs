@@ -0,0 +1,9 @@
+use insta::assert_snapshot;
+mod utils;
+
+#[test]
+fn test_args() {
+    let (out, err) = utils::run_upgrader("args", "0.3.0", true);
+    assert_snapshot!(out);
+    assert_snapshot!(err);
+}
@@ -0,0 +1,9 @@
+use insta::assert_snapshot;
+mod utils;
+
+#[test]
+fn test_attributes() {
+    let (out, err) = utils::run_upgrader("attributes", "0.3.0", false);
+    assert_snapshot!(out);
+    assert_snapshot!(err);
+}
@@ -0,0 +1,9 @@
+use insta::assert_snapshot;
+mod utils;
+
+#[test]
+fn test_dry_run() {
+    let (out, err) = utils::run_upgrader_with("rename_methods", "0.3.0", true, &["--dry-run"]);
+    assert_snapshot!(out);
+    assert_snapshot!(err);
+}
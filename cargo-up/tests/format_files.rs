@@ -0,0 +1,9 @@
+use insta::assert_snapshot;
+mod utils;
+
+#[test]
+fn test_format_files() {
+    let (out, err) = utils::run_upgrader_with("format", "0.6.0", true, &["--format=files"]);
+    assert_snapshot!(out);
+    assert_snapshot!(err);
+}
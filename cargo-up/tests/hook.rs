@@ -5,7 +5,8 @@ mod utils;
 #[test]
 #[serial]
 fn test_hook_on() {
-    let (out, err) = utils::run_upgrader("hook_on", "0.6.0", true);
+    let (out, err) =
+        utils::run_upgrader_with("hook_on", "0.6.0", true, &["--from-version", "0.5.0"]);
     assert_snapshot!(out);
     assert_snapshot!(err);
 }
@@ -0,0 +1,12 @@
+use insta::assert_snapshot;
+use serial_test::serial;
+mod utils;
+
+#[test]
+#[serial]
+fn test_hook_call_on() {
+    let (out, err) =
+        utils::run_upgrader_with("hook_call_on", "0.6.0", true, &["--from-version", "0.5.0"]);
+    assert_snapshot!(out);
+    assert_snapshot!(err);
+}
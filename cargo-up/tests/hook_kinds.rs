@@ -0,0 +1,11 @@
+use insta::assert_snapshot;
+use serial_test::serial;
+mod utils;
+
+#[test]
+#[serial]
+fn test_hook_kinds() {
+    let (out, err) = utils::run_upgrader("hook_kinds", "0.6.0", true);
+    assert_snapshot!(out);
+    assert_snapshot!(err);
+}
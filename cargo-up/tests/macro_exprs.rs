@@ -0,0 +1,10 @@
+use insta::assert_snapshot;
+mod utils;
+
+#[test]
+fn test_macro_exprs() {
+    let (out, err) =
+        utils::run_upgrader_with("macro_exprs", "0.9.0", true, &["--from-version", "0.8.0"]);
+    assert_snapshot!(out);
+    assert_snapshot!(err);
+}
@@ -0,0 +1,9 @@
+use insta::assert_snapshot;
+mod utils;
+
+#[test]
+fn test_manifest() {
+    let (out, err) = utils::run_upgrader_with("manifest", "0.7.0", true, &["--dry-run"]);
+    assert_snapshot!(out);
+    assert_snapshot!(err);
+}
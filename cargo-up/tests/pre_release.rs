@@ -0,0 +1,9 @@
+use insta::assert_snapshot;
+mod utils;
+
+#[test]
+fn test_pre_release() {
+    let (out, err) = utils::run_upgrader("pre_release", "0.3.0-rc.1+build.5", true);
+    assert_snapshot!(out);
+    assert_snapshot!(err);
+}
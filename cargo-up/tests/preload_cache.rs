@@ -0,0 +1,26 @@
+use insta::assert_snapshot;
+use std::fs::{read_dir, remove_dir_all};
+mod utils;
+
+#[test]
+fn test_preload_cache() {
+    let cache = utils::cache_dir();
+    let cache_arg = cache.to_string_lossy().to_string();
+    let args = ["--preload-cache", cache_arg.as_str()];
+
+    let (out, err) = utils::run_upgrader_with("reexports", "0.6.0", true, &args);
+    let cached = read_dir(&cache)
+        .unwrap()
+        .filter_map(|x| x.ok())
+        .any(|x| x.file_name().to_string_lossy().starts_with("upgradee-"));
+    let (cached_out, cached_err) = utils::run_upgrader_with("reexports", "0.6.0", true, &args);
+
+    remove_dir_all(&cache).unwrap();
+
+    assert!(cached);
+    assert!(err.contains("Preloading upgradee"));
+    // The dependency is not walked again when its index is read from the cache
+    assert!(!cached_err.contains("Preloading"));
+    assert_eq!(out, cached_out);
+    assert_snapshot!(cached_err);
+}
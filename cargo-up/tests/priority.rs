@@ -0,0 +1,9 @@
+use insta::assert_snapshot;
+mod utils;
+
+#[test]
+fn test_priority() {
+    let (out, err) = utils::run_upgrader("priority", "0.6.0", true);
+    assert_snapshot!(out);
+    assert_snapshot!(err);
+}
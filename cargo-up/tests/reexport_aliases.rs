@@ -0,0 +1,16 @@
+use insta::assert_snapshot;
+use serial_test::serial;
+mod utils;
+
+#[test]
+#[serial]
+fn test_reexport_aliases() {
+    let (out, err) = utils::run_upgrader_with(
+        "reexport_aliases",
+        "0.10.0",
+        true,
+        &["--from-version", "0.9.0"],
+    );
+    assert_snapshot!(out);
+    assert_snapshot!(err);
+}
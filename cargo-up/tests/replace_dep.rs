@@ -0,0 +1,9 @@
+use insta::assert_snapshot;
+mod utils;
+
+#[test]
+fn test_replace_dep() {
+    let (out, err) = utils::run_upgrader_with("replace_dep", "0.8.0", true, &["--dry-run"]);
+    assert_snapshot!(out);
+    assert_snapshot!(err);
+}
@@ -0,0 +1,10 @@
+use insta::assert_snapshot;
+mod utils;
+
+#[test]
+fn test_unmatched() {
+    let (out, err) =
+        utils::run_upgrader_with("unmatched", "0.11.0", true, &["--from-version", "0.10.0"]);
+    assert_snapshot!(out);
+    assert_snapshot!(err);
+}
@@ -0,0 +1,14 @@
+use insta::assert_snapshot;
+mod utils;
+
+#[test]
+fn test_unused_imports() {
+    let (out, err) = utils::run_upgrader_with(
+        "unused_imports",
+        "0.6.0",
+        true,
+        &["--remove-unused-imports"],
+    );
+    assert_snapshot!(out);
+    assert_snapshot!(err);
+}
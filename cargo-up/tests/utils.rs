@@ -2,11 +2,25 @@
 use assert_cmd::Command;
 use insta::assert_snapshot;
 use std::{
-    fs::{copy, read_to_string, write},
+    env::temp_dir,
+    fs::{copy, read_to_string, remove_dir_all, remove_file, write},
     path::PathBuf,
+    process,
     str::from_utf8,
+    sync::atomic::{AtomicUsize, Ordering},
 };
 
+static CACHES: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns a new directory to cache the preloaded dependencies in
+pub fn cache_dir() -> PathBuf {
+    temp_dir().join(format!(
+        "cargo-up-test-{}-{}",
+        process::id(),
+        CACHES.fetch_add(1, Ordering::SeqCst)
+    ))
+}
+
 pub fn run(dir: &str, args: &[&str]) -> (String, String) {
     let output = Command::cargo_bin("cargo-up")
         .unwrap()
@@ -36,7 +50,17 @@ pub fn run_err(dir: &str, args: &[&str]) -> String {
 }
 
 pub fn run_upgrader(dir: &str, version: &str, generate: bool) -> (String, String) {
+    run_upgrader_with(dir, version, generate, &[])
+}
+
+pub fn run_upgrader_with(
+    dir: &str,
+    version: &str,
+    generate: bool,
+    extra: &[&str],
+) -> (String, String) {
     let mut fixture_on = PathBuf::new();
+    let mut rustfmt = None;
 
     fixture_on.push("..");
     fixture_on.push("fixtures");
@@ -48,6 +72,20 @@ pub fn run_upgrader(dir: &str, version: &str, generate: bool) -> (String, String
         fixture_on.push("_run");
         let mut to = fixture_on.clone();
 
+        // The rustfmt config of the fixture is only used for its run
+        from.push("rustfmt.toml");
+        to.push("on");
+        to.push("rustfmt.toml");
+
+        if from.exists() {
+            copy(&from, &to).unwrap();
+            rustfmt = Some(to.clone());
+        }
+
+        from.pop();
+        to.pop();
+        to.pop();
+
         from.push("on.rs");
         to.push("on");
         to.push("src");
@@ -78,28 +116,41 @@ pub fn run_upgrader(dir: &str, version: &str, generate: bool) -> (String, String
 
     let original = read_to_string(&fixture_on).unwrap();
 
-    let (out, err) = run(
-        &on.to_string_lossy(),
-        &[
-            "up",
-            "dep",
-            "upgradee",
-            "--lib-path",
-            "../../../cargo-up",
-            "--upgrader-path",
-            "../../upgrader",
-            "--upgrader-name",
-            "upgrader",
-            "--dep-version",
-            version,
-            "--suppress-cargo-output",
-        ],
-    );
+    let mut args = vec![
+        "up",
+        "dep",
+        "upgradee",
+        "--lib-path",
+        "../../../cargo-up",
+        "--upgrader-path",
+        "../../upgrader",
+        "--upgrader-name",
+        "upgrader",
+        "--dep-version",
+        version,
+        "--suppress-cargo-output",
+    ];
+    args.extend(extra);
+
+    // Every run preloads the dependency unless a test shares the cache between its runs
+    let cache = cache_dir();
+    let cache_arg = cache.to_string_lossy().to_string();
+
+    if !extra.contains(&"--preload-cache") {
+        args.extend(&["--preload-cache", cache_arg.as_str()]);
+    }
+
+    let (out, err) = run(&on.to_string_lossy(), &args);
+    let _ = remove_dir_all(&cache);
 
     let file_content = read_to_string(&fixture_on).unwrap().replace("\r\n", "\n");
     assert_snapshot!(file_content);
 
     write(&fixture_on, original).unwrap();
 
+    if let Some(rustfmt) = rustfmt {
+        remove_file(rustfmt).unwrap();
+    }
+
     (out, err)
 }
@@ -0,0 +1,27 @@
+use upgradee::{connect, log, Builder};
+
+fn main() {
+    let b = Builder::new("name", 1);
+
+    b.set("key", 2).set(
+        "multi", // the key
+        3,
+    );
+    Builder::set(&b, "ufcs", 4);
+
+    b.flag(true, false);
+    b.flag(
+        true,
+        // whether to print more
+        false,
+    );
+
+    connect("localhost", 80);
+    upgradee::connect(
+        "localhost",
+        80,
+    );
+    connect("localhost", 80 /* http */);
+
+    log(1, "message");
+}
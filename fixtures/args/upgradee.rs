@@ -0,0 +1,19 @@
+pub struct Builder;
+
+impl Builder {
+    pub fn new(_name: &str, _size: usize) -> Self {
+        Builder
+    }
+
+    pub fn set(&self, _key: &str, _value: usize) -> &Self {
+        self
+    }
+
+    pub fn flag(&self, _on: bool, _verbose: bool) -> &Self {
+        self
+    }
+}
+
+pub fn connect(_host: &str, _port: u16) {}
+
+pub fn log(_level: u8, _msg: &str) {}
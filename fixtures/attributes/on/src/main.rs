@@ -0,0 +1,17 @@
+use upgradee::Builder;
+
+#[derive(Debug, Builder, upgradee::Display)]
+#[builder(name = "config", skip, setter(into, prefix = "with"))]
+struct Config {
+    #[builder(default)]
+    port: u16,
+    #[allow(dead_code)]
+    host: String,
+}
+
+#[upgradee::traced]
+fn run() {}
+
+fn main() {
+    run();
+}
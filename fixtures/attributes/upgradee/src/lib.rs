@@ -0,0 +1 @@
+pub use upgradee_derive::{traced, Builder, Display};
@@ -0,0 +1,9 @@
+use upgradee::split_text;
+
+fn untouched( ) -> u32 { 1 }
+
+fn main() {
+    let text = "a\n b\n";
+    let lines = split_text(text);
+    println!("{:?} {}", lines, untouched());
+}
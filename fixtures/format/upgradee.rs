@@ -0,0 +1,3 @@
+pub fn split_text(text: &str) -> Vec<&str> {
+    text.lines().collect()
+}
@@ -0,0 +1,15 @@
+use upgradee::{make, Build, Shape, Struct, Wrapper};
+
+fn local() -> Struct {
+    Struct::new()
+}
+
+fn main() {
+    let _ = make();
+    let _ = Struct::new();
+    let _ = Wrapper(1);
+    let _ = Shape::Circle(2);
+    let _ = <Struct as Build>::build();
+    let _ = Struct::build();
+    let _ = local();
+}
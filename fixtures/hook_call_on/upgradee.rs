@@ -0,0 +1,27 @@
+pub struct Struct {}
+
+impl Struct {
+    pub fn new() -> Self {
+        Struct {}
+    }
+}
+
+pub struct Wrapper(pub u32);
+
+pub enum Shape {
+    Circle(u32),
+}
+
+pub trait Build {
+    fn build() -> Self;
+}
+
+impl Build for Struct {
+    fn build() -> Self {
+        Struct {}
+    }
+}
+
+pub fn make() -> Struct {
+    Struct {}
+}
@@ -0,0 +1,45 @@
+use upgradee::Command;
+
+struct Build;
+
+enum Clean {
+    All,
+}
+
+struct Other;
+
+impl Command for Build {
+    fn create() -> Self {
+        Build
+    }
+
+    fn run(&self) {}
+}
+
+impl Command for Clean {
+    fn create() -> Self {
+        Clean::All
+    }
+
+    fn run(&self) {}
+}
+
+impl Other {
+    fn create() -> Self {
+        Other
+    }
+
+    fn run(&self) {}
+}
+
+fn main() {
+    let build = Build::create();
+    build.run();
+    (&build).run();
+
+    let clean = Clean::create();
+    clean.run();
+
+    let other = Other::create();
+    other.run();
+}
@@ -0,0 +1,5 @@
+pub trait Command {
+    fn create() -> Self;
+
+    fn run(&self);
+}
@@ -0,0 +1,41 @@
+use upgradee::{Config, Render};
+
+struct Page;
+
+impl Render for Page {
+    fn render(&self) -> String {
+        String::new()
+    }
+}
+
+impl Page {
+    fn size(&self) -> usize {
+        0
+    }
+}
+
+fn describe(config: &Config) -> &'static str {
+    match config.size {
+        0 => "empty",
+        _ => "full",
+    }
+}
+
+// Only the nodes written at the call site are hooked
+macro_rules! pick {
+    ($x:expr) => {
+        match $x {
+            0 => "empty",
+            _ => "full",
+        }
+    };
+}
+
+fn main() {
+    let config = Config { size: Page.size() as u32 };
+    let check = |c: &Config| c.size > 0;
+
+    describe(&config);
+    check(&config);
+    pick!(Page.size() as u32);
+}
@@ -0,0 +1,7 @@
+pub struct Config {
+    pub size: u32,
+}
+
+pub trait Render {
+    fn render(&self) -> String;
+}
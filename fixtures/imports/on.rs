@@ -0,0 +1,46 @@
+use upgradee::{builder::Command, launch};
+
+fn main() {
+    launch();
+    let _ = Command;
+}
+
+mod glob {
+    use upgradee::prelude::*;
+
+    pub fn f() {
+        upgradee::begin();
+    }
+}
+
+mod lines {
+    use upgradee::begin;
+    use upgradee::halt;
+
+    pub fn g() {
+        begin();
+        halt();
+    }
+}
+
+mod clash {
+    struct Parser;
+
+    pub fn h() {
+        upgradee::launch();
+    }
+}
+
+mod empty {
+    pub fn i() {
+        upgradee::halt();
+    }
+}
+
+mod imported {
+    use upgradee::{launch, Parser};
+
+    pub fn j() {
+        launch();
+    }
+}
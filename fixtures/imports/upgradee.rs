@@ -0,0 +1,19 @@
+pub trait Parser {
+    fn parse() -> Self;
+}
+
+pub struct Values;
+
+pub mod builder {
+    pub struct Command;
+}
+
+pub mod prelude {
+    pub use super::Values;
+}
+
+pub fn launch() {}
+
+pub fn begin() {}
+
+pub fn halt() {}
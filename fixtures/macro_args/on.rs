@@ -0,0 +1,22 @@
+use upgradee::{compute, Struct, MAX_SIZE};
+
+macro_rules! twice {
+    ($x:expr) => {
+        $x + $x
+    };
+}
+
+macro_rules! hidden {
+    () => {
+        upgradee::compute(1)
+    };
+}
+
+fn main() {
+    println!("{} {}", MAX_SIZE, compute(2));
+    let v = vec![Struct.print(), upgradee::MAX_SIZE];
+    assert_eq!(v.len(), compute(2));
+    let _ = twice!(compute(MAX_SIZE));
+    let _ = hidden!();
+    let _ = format!("{}", vec![compute(3)].len());
+}
@@ -0,0 +1,13 @@
+pub const MAX_SIZE: usize = 10;
+
+pub fn compute(x: usize) -> usize {
+    x
+}
+
+pub struct Struct;
+
+impl Struct {
+    pub fn print(&self) -> usize {
+        1
+    }
+}
@@ -0,0 +1,19 @@
+use upgradee::measure;
+
+macro_rules! same {
+    ($x:expr) => {
+        $x
+    };
+}
+
+macro_rules! twice {
+    ($x:expr) => {
+        $x + $x
+    };
+}
+
+fn main() {
+    let _ = measure(1) + measure(1);
+    let _ = same!(measure(2) + measure(2));
+    let _ = twice!(measure(3));
+}
@@ -0,0 +1,5 @@
+use upgradee::Struct;
+
+fn main() {
+    let _ = Struct {};
+}
@@ -0,0 +1,40 @@
+use upgradee::new::{Existing};
+use upgradee::old::Moved;
+use upgradee::old::{helper, Renamed as R};
+
+mod inner {
+    use upgradee::{new::Existing, old::{self, Moved}};
+
+    pub fn run(_: Existing, _: Moved, _: old::Renamed) {}
+}
+
+mod deep {
+    use upgradee::old::{self as legacy, helper};
+
+    pub fn run() -> upgradee::old::Renamed {
+        helper();
+        legacy::helper();
+        upgradee::old::Renamed
+    }
+}
+
+mod glob {
+    use upgradee::old::*;
+
+    pub fn run() {
+        helper();
+    }
+}
+
+fn main() {
+    let _: upgradee::old::Moved = upgradee::old::Moved;
+    let _ = (Existing, Moved, R);
+
+    match upgradee::old::Moved {
+        upgradee::old::Moved => helper(),
+    }
+
+    ::upgradee::old::helper();
+    deep::run();
+    glob::run();
+}
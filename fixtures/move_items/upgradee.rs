@@ -0,0 +1,11 @@
+pub mod old {
+    pub struct Moved;
+
+    pub struct Renamed;
+
+    pub fn helper() {}
+}
+
+pub mod new {
+    pub struct Existing;
+}
@@ -0,0 +1,11 @@
+use upgradee::{legacy, risky};
+
+fn main() {
+    let value = legacy();
+
+    if value > 0 {
+        risky(value);
+    }
+
+    println!("{}", legacy());
+}
@@ -0,0 +1,7 @@
+pub fn legacy() -> u32 {
+    0
+}
+
+pub fn risky(value: u32) -> u32 {
+    value
+}
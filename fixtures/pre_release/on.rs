@@ -0,0 +1,7 @@
+use upgradee::{compute, obsolete};
+
+fn main() {
+    compute(1);
+    obsolete();
+    upgradee::obsolete();
+}
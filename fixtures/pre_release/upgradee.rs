@@ -0,0 +1,5 @@
+pub fn compute(x: u32) -> u32 {
+    x
+}
+
+pub fn obsolete() {}
@@ -0,0 +1,7 @@
+use upgradee::Clock;
+
+fn main() {
+    let clock = Clock;
+
+    clock.tick();
+}
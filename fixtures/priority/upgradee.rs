@@ -0,0 +1,5 @@
+pub struct Clock;
+
+impl Clock {
+    pub fn tick(&self) {}
+}
@@ -0,0 +1,12 @@
+use upgradee::{Motor, Steer};
+
+struct Car;
+
+impl Steer for Car {
+    fn drive(&self) {}
+}
+
+fn main() {
+    Motor.ignite();
+    Car.drive();
+}
@@ -0,0 +1,14 @@
+mod engine {
+    pub struct Engine;
+
+    impl Engine {
+        pub fn ignite(&self) {}
+    }
+
+    pub trait Drive {
+        fn drive(&self);
+    }
+}
+
+pub use engine::Drive as Steer;
+pub use engine::Engine as Motor;
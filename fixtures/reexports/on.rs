@@ -0,0 +1,9 @@
+use upgradee::{parts, Engine};
+
+fn main() {
+    let engine: Engine = Engine { fuel: 10 };
+    engine.ignite();
+
+    let other: parts::Engine = engine;
+    let _ = other.fuel;
+}
@@ -0,0 +1,15 @@
+mod engine {
+    pub struct Engine {
+        pub fuel: u32,
+    }
+
+    impl Engine {
+        pub fn ignite(&self) {}
+    }
+}
+
+pub mod parts {
+    pub use crate::engine::Engine;
+}
+
+pub use engine::Engine;
@@ -0,0 +1,23 @@
+use upgradee::{Config, GREETING, MAX_SIZE};
+
+fn is_max(x: usize) -> bool {
+    match x {
+        MAX_SIZE => true,
+        _ => false,
+    }
+}
+
+fn is_default(x: u8) -> bool {
+    match x {
+        upgradee::Config::DEFAULT => true,
+        _ => false,
+    }
+}
+
+fn main() {
+    let _ = [0; MAX_SIZE];
+    let _ = is_max(upgradee::MAX_SIZE + 1);
+    let _: &str = GREETING;
+    let _ = upgradee::GREETING.len();
+    let _ = is_default(Config::DEFAULT);
+}
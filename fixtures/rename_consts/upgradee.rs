@@ -0,0 +1,9 @@
+pub const MAX_SIZE: usize = 10;
+
+pub static GREETING: &str = "hello";
+
+pub struct Config;
+
+impl Config {
+    pub const DEFAULT: u8 = 0;
+}
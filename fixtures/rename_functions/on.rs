@@ -0,0 +1,16 @@
+use upgradee::compute;
+use upgradee::compute as calc;
+use upgradee::inner::{self, helper};
+
+fn main() {
+    compute(1);
+    upgradee::compute(2);
+    calc(3);
+
+    let _: fn(u32) -> u32 = upgradee::compute;
+    let _ = Some(4).map(compute);
+
+    helper();
+    inner::helper();
+    upgradee::inner::helper();
+}
@@ -0,0 +1,7 @@
+pub fn compute(x: u32) -> u32 {
+    x
+}
+
+pub mod inner {
+    pub fn helper() {}
+}
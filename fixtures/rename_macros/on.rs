@@ -0,0 +1,17 @@
+#[macro_use(shout, whisper)]
+extern crate upgradee;
+
+mod inner {
+    use upgradee::shout;
+
+    pub fn run() -> String {
+        shout!("inner")
+    }
+}
+
+fn main() {
+    let _ = shout!("hello");
+    let _ = whisper!("HELLO");
+    let _ = upgradee::shout!("path");
+    inner::run();
+}
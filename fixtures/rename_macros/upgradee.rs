@@ -0,0 +1,13 @@
+#[macro_export]
+macro_rules! shout {
+    ($x:expr) => {
+        $x.to_uppercase()
+    };
+}
+
+#[macro_export]
+macro_rules! whisper {
+    ($x:expr) => {
+        $x.to_lowercase()
+    };
+}
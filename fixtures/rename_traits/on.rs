@@ -0,0 +1,32 @@
+use upgradee::{Dog, Speak};
+
+struct Cat;
+
+impl Speak for Cat {
+    fn speak(&self) -> String {
+        "meow".to_string()
+    }
+}
+
+fn talk<T: Speak>(x: &T) -> String {
+    x.speak()
+}
+
+fn boxed(x: Box<dyn Speak>) -> String {
+    x.shout()
+}
+
+fn make() -> impl upgradee::Speak {
+    Cat
+}
+
+fn main() {
+    Dog.speak();
+    Cat.speak();
+    Speak::speak(&Dog);
+    <Cat as Speak>::speak(&Cat);
+    Cat::speak(&Cat);
+    talk(&Cat);
+    boxed(Box::new(Dog));
+    make().shout();
+}
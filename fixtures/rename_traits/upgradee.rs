@@ -0,0 +1,15 @@
+pub trait Speak {
+    fn speak(&self) -> String;
+
+    fn shout(&self) -> String {
+        self.speak().to_uppercase()
+    }
+}
+
+pub struct Dog;
+
+impl Speak for Dog {
+    fn speak(&self) -> String {
+        "woof".to_string()
+    }
+}
@@ -0,0 +1,29 @@
+use upgradee::{Bits, Count, Shape};
+
+struct Wrapper(Shape);
+
+trait Area {
+    fn area(&self) -> Count;
+}
+
+impl Area for Shape {
+    fn area(&self) -> Count {
+        match self {
+            Shape::Circle => 1,
+            Shape::Square(x) => *x as Count,
+            upgradee::Shape::Rect { w } => *w as u32,
+        }
+    }
+}
+
+fn bits(b: Bits) -> u32 {
+    unsafe { b.int }
+}
+
+fn main() {
+    let shape = Shape::Square(2);
+    let _ = Wrapper(upgradee::Shape::Circle);
+    let _: upgradee::Count = shape.area();
+    let _ = bits(Bits { int: 1 });
+    let _ = upgradee::Bits { float: 1.0 };
+}
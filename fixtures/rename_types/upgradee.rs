@@ -0,0 +1,12 @@
+pub enum Shape {
+    Circle,
+    Square(u8),
+    Rect { w: u8 },
+}
+
+pub union Bits {
+    pub int: u32,
+    pub float: f32,
+}
+
+pub type Count = u32;
@@ -0,0 +1,13 @@
+extern crate upgradee;
+
+use upgradee::inner::{Deep, Inner as I};
+use upgradee::Struct as Aliased;
+use upgradee::{inner, Struct};
+
+fn main() {
+    let _: upgradee::Struct = Struct {};
+    let _ = upgradee::inner::Inner;
+    let _ = ::upgradee::Other;
+    let _ = Aliased {};
+    let _ = (I, Deep, inner::Inner);
+}
@@ -0,0 +1,9 @@
+pub struct Struct {}
+
+pub struct Other;
+
+pub mod inner {
+    pub struct Inner;
+
+    pub struct Deep;
+}
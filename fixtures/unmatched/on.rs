@@ -0,0 +1,5 @@
+use upgradee::compute;
+
+fn main() {
+    compute(1);
+}
@@ -0,0 +1,3 @@
+pub fn compute(x: u32) -> u32 {
+    x
+}
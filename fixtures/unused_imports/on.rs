@@ -0,0 +1,40 @@
+use std::fmt;
+use upgradee::retire;
+use upgradee::{retire as old, Greeter, Thing};
+
+fn main() {
+    retire();
+
+    let thing = Thing;
+    thing.greet();
+}
+
+mod inner {
+    use upgradee::{
+        retire,
+        Thing,
+    };
+
+    pub fn f() {
+        retire();
+        let _ = Thing;
+    }
+}
+
+mod nested {
+    use upgradee::retire;
+
+    pub fn g() {
+        retire();
+    }
+}
+
+mod exported {
+    pub use upgradee::retire;
+    #[cfg(test)]
+    use upgradee::Thing;
+
+    pub fn h() {
+        retire();
+    }
+}
@@ -0,0 +1,11 @@
+pub trait Greeter {
+    fn greet(&self) {}
+}
+
+pub struct Thing;
+
+impl Greeter for Thing {}
+
+pub fn retire() {}
+
+pub fn successor() {}
@@ -1,6 +1,9 @@
 use cargo_up::{
     anyhow::{bail, Result},
-    ra_ap_syntax::AstNode,
+    ra_ap_syntax::{
+        ast::{self, HasArgList},
+        AstNode,
+    },
     semver::Version as SemverVersion,
     Runner, Upgrader, Version,
 };
@@ -17,9 +20,15 @@ pub fn runner() -> Runner {
     Runner::new()
         .minimum("0.2.0")
         .unwrap()
+        .version(
+            Version::new("0.3.0-rc.0")
+                .unwrap()
+                .rename_functions("upgradee", &[["obsolete", "modern"]]),
+        )
         .version(
             Version::new("0.3.0")
                 .unwrap()
+                .peers(&["upgradee_derive"])
                 .rename_structs("upgradee", &[["OldStruct", "NewStruct"]])
                 .rename_members("upgradee::Struct", &[["struct_member", "new_s_member"]])
                 .rename_members("upgradee::Union", &[["union_member", "new_u_member"]])
@@ -31,6 +40,39 @@ pub fn runner() -> Runner {
                         ["Melon", "Papaya"],
                     ],
                 )
+                .rename_enums("upgradee", &[["Shape", "Form"]])
+                .rename_unions("upgradee", &[["Bits", "Raw"]])
+                .rename_type_aliases("upgradee", &[["Count", "Total"]])
+                .rename_consts("upgradee", &[["MAX_SIZE", "MAX_LEN"]])
+                .rename_consts("upgradee::Config", &[["DEFAULT", "EMPTY"]])
+                .rename_statics("upgradee", &[["GREETING", "WELCOME"]])
+                .rename_macros("upgradee", &[["shout", "yell"]])
+                .rename_derives("upgradee_derive", &[["Builder", "Constructor"]])
+                .rename_attribute_keys(
+                    "upgradee_derive",
+                    &[
+                        ["builder", "constructor"],
+                        ["builder::skip", "ignore"],
+                        ["builder::setter::prefix", "prefix_with"],
+                    ],
+                )
+                .hook_attr_on("upgradee_derive", "traced", |u, n, _| {
+                    u.replace(n.path(), "instrument");
+                })
+                .reorder_args("upgradee::Builder", "set", &[1, 0])
+                .wrap_arg("upgradee::Builder", "new", 0, "String::from({})")
+                .remove_arg("upgradee::Builder", "flag", 1)
+                .insert_arg("upgradee", "connect", 2, "None")
+                .remove_arg("upgradee", "log", 0)
+                .rename_functions("upgradee", &[["compute", "calculate"]])
+                .rename_functions("upgradee::inner", &[["helper", "assist"]])
+                .rename_traits("upgradee", &[["Speak", "Talk"]])
+                .rename_trait_methods("upgradee::Speak", &[["speak", "say"]])
+                .move_items(&[
+                    ["upgradee::old::Moved", "upgradee::new::Moved"],
+                    ["upgradee::old::Renamed", "upgradee::new::Fresh"],
+                    ["upgradee::old::helper", "upgradee::helper"],
+                ])
                 .rename_methods("upgradee::Struct", &[["print", "print_err"]])
                 .rename_methods("upgradee::Enum", &[["talk", "talk_err"]])
                 .rename_methods("upgradee::Union", &[["eat", "eat_err"]]),
@@ -41,11 +83,149 @@ pub fn runner() -> Runner {
                 .rename_methods("structopt::StructOpt", &[["from_args", "parse"]]),
         )
         .version(Version::new("0.5.0").unwrap().init(init))
-        .version(Version::new("0.6.0").unwrap().hook_method_call_expr_on(
-            "upgradee::Struct",
-            "print",
+        .version(
+            Version::new("0.6.0")
+                .unwrap()
+                .hook_method_call_expr_on("upgradee::Struct", "print", |u, n, _| {
+                    u.replace(n.syntax().text_range(), "member");
+                })
+                .rename_methods("upgradee::Struct", &[["print", "print_err"]])
+                .hook_call_expr_on("upgradee", "make", |u, n, _| {
+                    u.insert(n.syntax().text_range().end(), " /* function */");
+                })
+                .hook_call_expr_on("upgradee::Struct", "new", |u, n, _| {
+                    u.insert(n.syntax().text_range().end(), " /* associated function */");
+                })
+                .hook_call_expr_on("upgradee", "Wrapper", |u, n, _| {
+                    u.insert(n.syntax().text_range().end(), " /* tuple struct */");
+                })
+                .hook_call_expr_on("upgradee::Shape", "Circle", |u, n, _| {
+                    u.insert(n.syntax().text_range().end(), " /* tuple variant */");
+                })
+                .hook_call_expr_on("upgradee::Build", "build", |u, n, _| {
+                    u.insert(n.syntax().text_range().end(), " /* trait function */");
+                })
+                .hook_match_arm(|u, n, _| {
+                    u.insert(n.syntax().text_range().start(), "/* arm */ ");
+                })
+                .hook_closure_expr(|u, n, _| {
+                    u.insert(n.syntax().text_range().end(), " /* closure */");
+                })
+                .hook_cast_expr(|u, n, _| {
+                    u.insert(n.syntax().text_range().end(), " /* cast */");
+                })
+                .hook_path_type_on("upgradee", "Config", |u, n, _| {
+                    u.replace(n.syntax().text_range(), "Settings");
+                })
+                .hook_impl_on("upgradee", "Render", |u, n, _| {
+                    u.insert(n.syntax().text_range().start(), "// Implements Render\n");
+                })
+                .hook_impl_of("upgradee::Command", |u, n, _| {
+                    u.insert(n.syntax().text_range().start(), "#[derive(Debug)]\n");
+                })
+                .hook_method_call_expr_on_impls("upgradee::Command", "run", |u, n, _| {
+                    u.replace(n.name_ref().unwrap().syntax().text_range(), "execute");
+                })
+                .hook_call_expr_on_impls("upgradee::Command", "create", |u, n, _| {
+                    u.insert(n.syntax().text_range().end(), ".unwrap()");
+                })
+                .hook_call_expr_on("upgradee", "legacy", |u, n, _| {
+                    u.todo(n.syntax().text_range(), "Replace `legacy` with `modern`");
+                })
+                .hook_call_expr_on("upgradee", "risky", |u, n, _| {
+                    u.warn(n.syntax().text_range(), "`risky` now panics on zero");
+                })
+                .hook_call_expr_on("upgradee", "launch", |u, n, _| {
+                    u.ensure_import(n.syntax().text_range(), "upgradee::Parser");
+                    u.ensure_import(n.syntax().text_range(), "std::vec::Vec");
+                })
+                .hook_call_expr_on("upgradee", "begin", |u, n, _| {
+                    u.ensure_import(n.syntax().text_range(), "upgradee::prelude::Values");
+                    u.ensure_import(n.syntax().text_range(), "upgradee::Parser");
+                })
+                .hook_call_expr_on("upgradee", "halt", |u, n, _| {
+                    u.ensure_import(n.syntax().text_range(), "upgradee::Parser");
+                })
+                .hook_call_expr_on("upgradee", "retire", |u, n, _| {
+                    u.replace(n.syntax().text_range(), "upgradee::successor()");
+                })
+                .hook_method_call_expr_on("upgradee::Engine", "ignite", |u, n, _| {
+                    u.insert(n.syntax().text_range().end(), " /* public path */");
+                })
+                .hook_field_expr_on("upgradee::parts::Engine", "fuel", |u, n, _| {
+                    u.insert(n.syntax().text_range().end(), " /* nested public path */");
+                })
+                .hook_path_type_on("upgradee::engine", "Engine", |u, n, _| {
+                    u.insert(n.syntax().text_range().end(), " /* private path */");
+                })
+                .hook_call_expr_on("upgradee::Missing", "new", |u, n, _| {
+                    u.insert(n.syntax().text_range().end(), " /* unreachable */");
+                })
+                .hook_call_expr_on("upgradee", "split_text", |u, n, _| {
+                    let text = n.arg_list().unwrap().args().next().unwrap();
+                    u.replace(
+                        n.syntax().text_range(),
+                        format!(
+                            "{}.lines().map(|l| l.trim()).filter(|l| !l.is_empty()).collect::<Vec<_>>()",
+                            text
+                        ),
+                    );
+                })
+                .hook_method_call_expr_on("upgradee::Clock", "tick", |u, n, _| {
+                    u.replace(n.syntax().text_range(), "Clock::now()");
+                })
+                .priority(1)
+                .rename_methods("upgradee::Clock", &[["tick", "step"]]),
+        )
+        .version(Version::new("0.7.0").unwrap().init(|u, _| {
+            u.add_dep("anyhow", "1.0");
+            u.add_feature("upgradee", "full");
+            Ok(())
+        }))
+        .version(Version::new("0.8.0").unwrap().replace_dep(
+            "upgradee",
+            "upgraded",
+            "0.8.0",
+            &["full"],
+            &[
+                ["upgradee::Struct", "upgraded::NewStruct"],
+                ["upgradee::inner", "upgraded::inner2"],
+                ["upgradee::inner::Deep", "upgraded::Deep"],
+            ],
+        ))
+        .version(Version::new("0.9.0").unwrap().hook_call_expr_on(
+            "upgradee",
+            "measure",
             |u, n, _| {
-                u.replace(n.syntax().text_range(), "member");
+                let sum = match n.syntax().parent().and_then(ast::BinExpr::cast) {
+                    Some(x) => x,
+                    None => return,
+                };
+
+                if sum.lhs().as_ref().map(|x| x.syntax()) == Some(n.syntax())
+                    && sum.rhs().map(|x| x.to_string()) == Some(n.to_string())
+                {
+                    u.replace(sum.syntax().text_range(), format!("2 * {}", n));
+                }
             },
         ))
+        .version(
+            Version::new("0.10.0")
+                .unwrap()
+                .hook_method_call_expr_on("upgradee::Motor", "ignite", |u, n, _| {
+                    u.insert(n.syntax().text_range().end(), " /* aliased type */");
+                })
+                .hook_impl_of("upgradee::Steer", |u, n, _| {
+                    u.insert(n.syntax().text_range().start(), "// Implements Steer\n");
+                })
+                .hook_method_call_expr_on_impls("upgradee::Steer", "drive", |u, n, _| {
+                    u.insert(n.syntax().text_range().end(), " /* aliased trait */");
+                }),
+        )
+        .version(
+            Version::new("0.11.0")
+                .unwrap()
+                .rename_functions("upgradee", &[["compute", "calculate"], ["obsolete", "modern"]])
+                .rename_methods("upgradee::Missing", &[["old", "new"]]),
+        )
 }